@@ -0,0 +1,88 @@
+// Builds a polyglot opening book from the games of a PGN file.
+//
+//     cargo run --release --example book -- <games.pgn> <book.bin> [options]
+//
+// Options:
+//   --max-ply N    only use the first N plies of each game
+//   --min-elo N    only use moves by players rated N or more
+//   --min-games N  drop moves played in fewer than N games (default 1)
+//   --merge FILE   merge the result into an existing book
+// Games that cannot be replayed are reported and skipped.
+use std::env;
+use std::fs;
+use std::process;
+
+use tuatara::pgn;
+use tuatara::{Book, BookBuilder};
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: book <games.pgn> <book.bin> [--max-ply N] [--min-elo N] [--min-games N] [--merge FILE]"
+    );
+    process::exit(2);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let [input, output, options @ ..] = args.as_slice() else {
+        usage();
+    };
+    let mut max_ply = None;
+    let mut min_elo = None;
+    let mut min_games = 1;
+    let mut merge = None;
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let value = options.next().unwrap_or_else(|| usage());
+        let number = || {
+            value.parse::<u32>().unwrap_or_else(|_| {
+                eprintln!("not a number: {value}");
+                process::exit(2);
+            })
+        };
+        match option.as_str() {
+            "--max-ply" => max_ply = Some(number() as usize),
+            "--min-elo" => min_elo = Some(number()),
+            "--min-games" => min_games = number(),
+            "--merge" => merge = Some(value),
+            _ => usage(),
+        }
+    }
+
+    let text = fs::read_to_string(input).unwrap_or_else(|err| {
+        eprintln!("cannot read {input}: {err}");
+        process::exit(1);
+    });
+    let mut builder = BookBuilder::new(min_games);
+    if let Some(max_ply) = max_ply {
+        builder = builder.max_ply(max_ply);
+    }
+    if let Some(min_elo) = min_elo {
+        builder = builder.min_elo(min_elo);
+    }
+    let games = pgn::parse(&text);
+    let mut skipped = 0;
+    for (i, game) in games.iter().enumerate() {
+        if let Err(err) = builder.add_game(game) {
+            eprintln!("game {}: {err}", i + 1);
+            skipped += 1;
+        }
+    }
+    let mut book = builder.build();
+    if let Some(path) = merge {
+        let other = Book::open(path).unwrap_or_else(|err| {
+            eprintln!("cannot open {path}: {err}");
+            process::exit(1);
+        });
+        book = book.merge(&other);
+    }
+    if let Err(err) = book.save(output) {
+        eprintln!("cannot write {output}: {err}");
+        process::exit(1);
+    }
+    eprintln!(
+        "{} games ({skipped} skipped), {} entries written to {output}",
+        games.len(),
+        book.len()
+    );
+}
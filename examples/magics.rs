@@ -0,0 +1,436 @@
+// Searches for rook and bishop magics with per-square shifts, packs them
+// into one shared attack table and prints MAGIC_TABLE_SIZE, ROOK_MAGICS
+// and BISHOP_MAGICS as Rust source that can replace those in consts.rs.
+//
+//     cargo run --release --example magics -- [seed] [candidates] [rounds]
+//
+// Each square gets `candidates` (default 4) random magics for every index
+// width from the bit count of its mask up to 12 bits for rooks and 9 for
+// bishops, plus its current magic. Wider indices leave more entries free,
+// so the slices of different squares can overlap where they agree.
+//
+// Two layouts are packed: the current one from consts.rs, and a greedy one
+// that places the biggest slices first, each with the candidate that ends
+// lowest. Both are improved by moving slices to lower offsets, then for
+// `rounds` (default 1000) rounds by taking out the slices at the end of the
+// table with a few of their neighbours and putting them back, and the
+// smaller table is printed. Starting from the current layout means the
+// result is never bigger than the table in consts.rs.
+use std::env;
+use std::process;
+use std::time::Instant;
+
+use tuatara::{
+    BB_FILE_A, BB_FILE_H, BB_RANK_1, BB_RANK_8, BISHOP_MAGICS, BitBoard, FILES, MAGIC_TABLE_SIZE,
+    Magic, Prng, RANKS, ROOK_MAGICS, Square,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Slider {
+    Rook,
+    Bishop,
+}
+impl Slider {
+    const fn max_bits(self) -> u32 {
+        match self {
+            Slider::Rook => 12,
+            Slider::Bishop => 9,
+        }
+    }
+    const fn name(self) -> &'static str {
+        match self {
+            Slider::Rook => "ROOK_MAGICS",
+            Slider::Bishop => "BISHOP_MAGICS",
+        }
+    }
+    fn current(self, sq: Square) -> &'static Magic {
+        match self {
+            Slider::Rook => &ROOK_MAGICS[sq.index()],
+            Slider::Bishop => &BISHOP_MAGICS[sq.index()],
+        }
+    }
+    // Reference attacks from the occluded fills, independent of the
+    // tables we are replacing.
+    fn attacks(self, sq: Square, occupied: BitBoard) -> BitBoard {
+        let bb = sq.as_bb();
+        let empty = !occupied;
+        match self {
+            Slider::Rook => {
+                bb.north_occluded(empty).north_one()
+                    | bb.south_occluded(empty).south_one()
+                    | bb.east_occluded(empty).east_one()
+                    | bb.west_occluded(empty).west_one()
+            }
+            Slider::Bishop => {
+                bb.north_east_occluded(empty).north_east_one()
+                    | bb.north_west_occluded(empty).north_west_one()
+                    | bb.south_east_occluded(empty).south_east_one()
+                    | bb.south_west_occluded(empty).south_west_one()
+            }
+        }
+    }
+    // Squares whose occupancy changes the attacks: the empty-board attacks
+    // without the board edges the slider cannot see past.
+    fn mask(self, sq: Square) -> BitBoard {
+        let edges = ((BB_RANK_1 | BB_RANK_8) - RANKS[sq.rank()])
+            | ((BB_FILE_A | BB_FILE_H) - FILES[sq.file()]);
+        self.attacks(sq, BitBoard::EMPTY) - edges
+    }
+}
+
+// One working magic for a square: the used entries of its slice, relative
+// to the offset, and the length of the slice up to the last of them.
+struct Candidate {
+    factor: u64,
+    shift: u32,
+    used: Vec<(usize, u64)>,
+    len: usize,
+}
+
+// The occupancy subsets of one square with their attacks, and its
+// candidate magics.
+struct Slice {
+    slider: Slider,
+    sq: Square,
+    mask: u64,
+    subsets: Vec<(u64, u64)>,
+    candidates: Vec<Candidate>,
+}
+impl Slice {
+    fn new(slider: Slider, sq: Square) -> Self {
+        let mask = slider.mask(sq).bits();
+        let mut subsets = Vec::new();
+        let mut subset = 0u64;
+        loop {
+            subsets.push((subset, slider.attacks(sq, BitBoard::new(subset)).bits()));
+            subset = subset.wrapping_sub(mask) & mask;
+            if subset == 0 {
+                break;
+            }
+        }
+        Slice {
+            slider,
+            sq,
+            mask,
+            subsets,
+            candidates: Vec::new(),
+        }
+    }
+    fn try_factor(&self, factor: u64, shift: u32, slots: &mut Vec<u64>) -> Option<Candidate> {
+        slots.clear();
+        slots.resize(1 << (64 - shift), 0);
+        for &(subset, attacks) in &self.subsets {
+            let idx = (subset.wrapping_mul(factor) >> shift) as usize;
+            // attacks are never empty, so 0 marks a free slot
+            if slots[idx] != 0 && slots[idx] != attacks {
+                return None;
+            }
+            slots[idx] = attacks;
+        }
+        let used: Vec<(usize, u64)> = (0..slots.len())
+            .filter(|&i| slots[i] != 0)
+            .map(|i| (i, slots[i]))
+            .collect();
+        let len = used.last().unwrap().0 + 1;
+        Some(Candidate {
+            factor,
+            shift,
+            used,
+            len,
+        })
+    }
+    fn find_candidates(&mut self, prng: &mut Prng, count: u32) {
+        let mut slots = Vec::new();
+        let current = self.slider.current(self.sq);
+        assert_eq!(current.mask, self.mask);
+        let candidate = self.try_factor(current.factor, current.shift, &mut slots);
+        self.candidates
+            .push(candidate.expect("current magic does not work"));
+        for bits in self.mask.count_ones()..=self.slider.max_bits() {
+            let mut found = 0;
+            while found < count {
+                let factor = prng.sparse_u64();
+                // a good factor moves plenty of mask bits into the index
+                if (self.mask.wrapping_mul(factor) >> 56).count_ones() < 6 {
+                    continue;
+                }
+                if let Some(candidate) = self.try_factor(factor, 64 - bits, &mut slots) {
+                    self.candidates.push(candidate);
+                    found += 1;
+                }
+            }
+        }
+    }
+}
+
+// A shared attack table with the candidate and offset of every slice.
+// Entries count the slices using them, so a slice can be taken out again.
+#[derive(Clone)]
+struct Layout {
+    entries: Vec<u64>,
+    users: Vec<u16>,
+    placed: Vec<Option<(usize, usize)>>,
+}
+impl Layout {
+    fn new(slices: usize) -> Self {
+        Layout {
+            entries: Vec::new(),
+            users: Vec::new(),
+            placed: vec![None; slices],
+        }
+    }
+    fn size(&self, slices: &[Slice]) -> usize {
+        (0..slices.len())
+            .map(|i| self.end(slices, i))
+            .max()
+            .unwrap_or(0)
+    }
+    fn end(&self, slices: &[Slice], i: usize) -> usize {
+        self.placed[i].map_or(0, |(c, offset)| offset + slices[i].candidates[c].len)
+    }
+    fn fits(&self, offset: usize, candidate: &Candidate) -> bool {
+        candidate.used.iter().all(|&(i, attacks)| {
+            let entry = self.entries.get(offset + i).copied().unwrap_or(0);
+            entry == 0 || entry == attacks
+        })
+    }
+    // The placement of the slice that ends lowest, if any ends below `limit`.
+    fn best_fit(&self, slice: &Slice, limit: usize) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize, usize)> = None;
+        for (c, candidate) in slice.candidates.iter().enumerate() {
+            let limit = best.map_or(limit, |(end, _, _)| end);
+            let offset = (0..limit.saturating_sub(candidate.len))
+                .find(|&offset| self.fits(offset, candidate));
+            if let Some(offset) = offset {
+                best = Some((offset + candidate.len, c, offset));
+            }
+        }
+        best.map(|(_, c, offset)| (c, offset))
+    }
+    fn place(&mut self, slices: &[Slice], i: usize, c: usize, offset: usize) {
+        let candidate = &slices[i].candidates[c];
+        let end = offset + candidate.len;
+        if self.entries.len() < end {
+            self.entries.resize(end, 0);
+            self.users.resize(end, 0);
+        }
+        for &(idx, attacks) in &candidate.used {
+            self.entries[offset + idx] = attacks;
+            self.users[offset + idx] += 1;
+        }
+        self.placed[i] = Some((c, offset));
+    }
+    fn remove(&mut self, slices: &[Slice], i: usize) -> (usize, usize) {
+        let (c, offset) = self.placed[i].take().unwrap();
+        for &(idx, _) in &slices[i].candidates[c].used {
+            self.users[offset + idx] -= 1;
+            if self.users[offset + idx] == 0 {
+                self.entries[offset + idx] = 0;
+            }
+        }
+        (c, offset)
+    }
+    // Moves a slice to wherever it ends lowest; false if it already does.
+    fn lower(&mut self, slices: &[Slice], i: usize) -> bool {
+        let end = self.end(slices, i);
+        let (c, offset) = self.remove(slices, i);
+        match self.best_fit(&slices[i], end) {
+            Some((c, offset)) => {
+                self.place(slices, i, c, offset);
+                true
+            }
+            None => {
+                self.place(slices, i, c, offset);
+                false
+            }
+        }
+    }
+    // Lowers the slices that end the table, and when none of them can
+    // move, every other slice to open up room, until nothing moves.
+    fn improve(&mut self, slices: &[Slice]) {
+        loop {
+            let size = self.size(slices);
+            let tail: Vec<usize> = (0..slices.len())
+                .filter(|&i| self.end(slices, i) == size)
+                .collect();
+            let mut moved = false;
+            for i in tail {
+                moved |= self.lower(slices, i);
+            }
+            if moved {
+                continue;
+            }
+            let mut order: Vec<usize> = (0..slices.len()).collect();
+            order.sort_by_key(|&i| std::cmp::Reverse(self.end(slices, i)));
+            for i in order {
+                moved |= self.lower(slices, i);
+            }
+            if !moved {
+                return;
+            }
+        }
+    }
+}
+
+// Takes out the slices that end the table together with a few random
+// others and puts them back, biggest first, wherever they end lowest.
+// Layouts that are no bigger are kept, so the search can drift across
+// plateaus; bigger ones are dropped.
+const NEAR_END: usize = 8192;
+fn shake(layout: &mut Layout, slices: &[Slice], prng: &mut Prng, rounds: u32) {
+    for _ in 0..rounds {
+        let size = layout.size(slices);
+        let mut next = layout.clone();
+        let mut taken: Vec<usize> = (0..slices.len())
+            .filter(|&i| next.end(slices, i) == size)
+            .collect();
+        // slices near the end of the table are the ones in the way
+        let near: Vec<usize> = (0..slices.len())
+            .filter(|&i| next.end(slices, i) + NEAR_END > size)
+            .collect();
+        for _ in 0..1 + prng.below(4) {
+            let i = near[prng.below(near.len() as u64) as usize];
+            if !taken.contains(&i) {
+                taken.push(i);
+            }
+        }
+        for &i in &taken {
+            next.remove(slices, i);
+        }
+        taken.sort_by_key(|&i| std::cmp::Reverse(slices[i].subsets.len()));
+        for &i in &taken {
+            let (c, offset) = next.best_fit(&slices[i], usize::MAX).unwrap();
+            next.place(slices, i, c, offset);
+        }
+        if next.size(slices) <= size {
+            *layout = next;
+        }
+    }
+}
+
+fn current_layout(slices: &[Slice]) -> Layout {
+    let mut layout = Layout::new(slices.len());
+    for (i, slice) in slices.iter().enumerate() {
+        // the current magic is always the first candidate
+        let offset = slice.slider.current(slice.sq).offset;
+        assert!(layout.fits(offset, &slice.candidates[0]));
+        layout.place(slices, i, 0, offset);
+    }
+    layout
+}
+
+fn greedy_layout(slices: &[Slice]) -> Layout {
+    let mut layout = Layout::new(slices.len());
+    // the biggest slices are the hardest to fit, so place them first
+    let mut order: Vec<usize> = (0..slices.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(slices[i].subsets.len()));
+    for i in order {
+        let (c, offset) = layout.best_fit(&slices[i], usize::MAX).unwrap();
+        layout.place(slices, i, c, offset);
+    }
+    layout
+}
+
+// Rebuilds every lookup from the emitted numbers and checks it against
+// the reference attacks.
+fn verify(slices: &[Slice], magics: &[Magic], entries: &[u64]) {
+    for (slice, magic) in slices.iter().zip(magics) {
+        for &(subset, attacks) in &slice.subsets {
+            let idx = ((subset & magic.mask).wrapping_mul(magic.factor) >> magic.shift) as usize;
+            assert_eq!(entries[magic.offset + idx], attacks, "{}", slice.sq);
+        }
+    }
+}
+
+fn hex(x: u64) -> String {
+    format!(
+        "0x{:04x}_{:04x}_{:04x}_{:04x}",
+        x >> 48,
+        (x >> 32) & 0xffff,
+        (x >> 16) & 0xffff,
+        x & 0xffff
+    )
+}
+
+fn emit(slider: Slider, magics: &[Magic]) {
+    println!("#[rustfmt::skip]");
+    println!("pub static {}: [Magic; 64] = [", slider.name());
+    for magic in magics {
+        println!(
+            "    Magic {{ mask: {}, factor: {}, shift: {}, offset: {:>5} }},",
+            hex(magic.mask),
+            hex(magic.factor),
+            magic.shift,
+            magic.offset
+        );
+    }
+    println!("];");
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let parse = |i: usize, default: u64| match args.get(i) {
+        None => default,
+        Some(s) => s.parse().unwrap_or_else(|_| {
+            eprintln!("not a number: {s}");
+            process::exit(2);
+        }),
+    };
+    let seed = parse(0, 1);
+    let candidates = parse(1, 4).max(1) as u32;
+    let rounds = parse(2, 1000) as u32;
+
+    let start = Instant::now();
+    let mut prng = Prng::new(seed);
+    let mut slices: Vec<Slice> = Square::all()
+        .map(|sq| Slice::new(Slider::Rook, sq))
+        .chain(Square::all().map(|sq| Slice::new(Slider::Bishop, sq)))
+        .collect();
+    for slice in &mut slices {
+        slice.find_candidates(&mut prng, candidates);
+    }
+
+    let mut best: Option<Layout> = None;
+    for (name, mut layout) in [
+        ("current", current_layout(&slices)),
+        ("greedy", greedy_layout(&slices)),
+    ] {
+        let packed = layout.size(&slices);
+        layout.improve(&slices);
+        shake(&mut layout, &slices, &mut prng, rounds);
+        layout.improve(&slices);
+        let size = layout.size(&slices);
+        eprintln!("{name} layout: {packed} entries, {size} after improving");
+        if best.as_ref().is_none_or(|best| size < best.size(&slices)) {
+            best = Some(layout);
+        }
+    }
+    let layout = best.unwrap();
+    let size = layout.size(&slices);
+
+    let magics: Vec<Magic> = slices
+        .iter()
+        .zip(&layout.placed)
+        .map(|(slice, placed)| {
+            let (c, offset) = placed.unwrap();
+            let candidate = &slice.candidates[c];
+            Magic {
+                mask: slice.mask,
+                factor: candidate.factor,
+                shift: candidate.shift,
+                offset,
+            }
+        })
+        .collect();
+    verify(&slices, &magics, &layout.entries);
+
+    println!("pub const MAGIC_TABLE_SIZE: usize = {size};");
+    emit(Slider::Rook, &magics[..64]);
+    emit(Slider::Bishop, &magics[64..]);
+    let free = layout.entries.iter().filter(|&&entry| entry == 0).count();
+    eprintln!(
+        "table size {size} entries ({free} unused, {:+} against the current {MAGIC_TABLE_SIZE}), {:.1?}",
+        size as i64 - MAGIC_TABLE_SIZE as i64,
+        start.elapsed()
+    );
+}
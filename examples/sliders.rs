@@ -0,0 +1,56 @@
+// Micro-benchmark of the slider attack backends.
+//
+//     cargo run --release --example sliders [lookups]
+//
+// Every backend answers the same pseudo-random (square, occupancy)
+// queries, so the checksums must agree; throughput is reported in
+// millions of rook plus bishop lookups per second.
+use std::env;
+use std::hint::black_box;
+use std::time::Instant;
+
+use tuatara::sliders::{Classical, FancyMagic, Hyperbola, KoggeStone, Loop, Pext, Sliders};
+use tuatara::{BitBoard, Prng, Square};
+
+const QUERIES: usize = 4096;
+
+fn run<S: Sliders>(queries: &[(Square, BitBoard)], lookups: usize) {
+    let rounds = lookups.div_ceil(queries.len()).max(1);
+    let start = Instant::now();
+    let mut checksum = 0u64;
+    for _ in 0..rounds {
+        for &(sq, occupied) in black_box(queries) {
+            checksum = checksum.wrapping_add(S::rook_attacks(sq, occupied).bits());
+            checksum = checksum.wrapping_add(S::bishop_attacks(sq, occupied).bits());
+        }
+    }
+    let elapsed = start.elapsed();
+    let total = (rounds * queries.len()) as f64;
+    println!(
+        "{:<12} {:>8.1} M/s  {:>8.1?}  checksum {checksum:016x}",
+        S::NAME,
+        total / elapsed.as_secs_f64() / 1e6,
+        elapsed
+    );
+}
+
+fn main() {
+    let lookups = env::args()
+        .nth(1)
+        .map_or(10_000_000, |s| s.parse().expect("lookups must be a number"));
+    let mut prng = Prng::new(1);
+    let squares: Vec<Square> = Square::all().collect();
+    let queries: Vec<(Square, BitBoard)> = (0..QUERIES)
+        .map(|_| {
+            let sq = squares[prng.below(64) as usize];
+            (sq, BitBoard::new(prng.sparse_u64() | prng.sparse_u64()))
+        })
+        .collect();
+
+    run::<FancyMagic>(&queries, lookups);
+    run::<Pext>(&queries, lookups);
+    run::<Hyperbola>(&queries, lookups);
+    run::<KoggeStone>(&queries, lookups);
+    run::<Classical>(&queries, lookups);
+    run::<Loop>(&queries, lookups);
+}
@@ -1,6 +1,6 @@
 use crate::consts::*;
 
-const fn sliding_attacks(square: i32, occupied: u64, deltas: &[i32]) -> u64 {
+pub(crate) const fn sliding_attacks(square: i32, occupied: u64, deltas: &[i32]) -> u64 {
     let mut attack = 0;
 
     let mut i = 0;
@@ -40,17 +40,35 @@ static PAWN_ATTACKS: [[u64; 64]; 2] = [
     bootstrap_stepping_attacks(&WHITE_PAWN_DELTAS),
     bootstrap_stepping_attacks(&BLACK_PAWN_DELTAS),
 ];
+/// The squares a pawn of the given colour attacks.
+///
+/// ```
+/// use tuatara::{attacks, Col, Square};
+///
+/// let bb = attacks::pawn_attacks(Col::White, Square::E4);
+/// assert_eq!(bb, Square::D5.as_bb() | Square::F5.as_bb());
+/// ```
 pub const fn pawn_attacks(col: Col, sq: Square) -> BitBoard {
-    PAWN_ATTACKS[col.index()][sq.index()]
+    BitBoard(PAWN_ATTACKS[col.index()][sq.index()])
 }
+/// ```
+/// use tuatara::{attacks, Square};
+///
+/// assert_eq!(attacks::knight_attacks(Square::A1), Square::B3.as_bb() | Square::C2.as_bb());
+/// ```
 pub const fn knight_attacks(sq: Square) -> BitBoard {
-    KNIGHT_ATTACKS[sq.index()]
+    BitBoard(KNIGHT_ATTACKS[sq.index()])
 }
+/// ```
+/// use tuatara::{attacks, Square};
+///
+/// assert_eq!(attacks::king_attacks(Square::E4).popcount(), 8);
+/// ```
 pub const fn king_attacks(sq: Square) -> BitBoard {
-    KING_ATTACKS[sq.index()]
+    BitBoard(KING_ATTACKS[sq.index()])
 }
-const fn bootstrap_magics() -> [u64; 88772] {
-    let mut table = [0; 88772];
+const fn bootstrap_magics() -> [u64; MAGIC_TABLE_SIZE] {
+    let mut table = [0; MAGIC_TABLE_SIZE];
     let mut square = 0;
     while square < 64 {
         let magic = &BISHOP_MAGICS[square as usize];
@@ -58,7 +76,7 @@ const fn bootstrap_magics() -> [u64; 88772] {
         let mut subset = 0;
         loop {
             let attack = sliding_attacks(square, subset, &BISHOP_DELTAS);
-            let idx = (magic.factor.wrapping_mul(subset) >> (64 - 9)) as usize + magic.offset;
+            let idx = (magic.factor.wrapping_mul(subset) >> magic.shift) as usize + magic.offset;
             assert!(table[idx] == 0 || table[idx] == attack);
             table[idx] = attack;
             subset = subset.wrapping_sub(range) & range;
@@ -72,7 +90,7 @@ const fn bootstrap_magics() -> [u64; 88772] {
         let mut subset = 0;
         loop {
             let attack = sliding_attacks(square, subset, &ROOK_DELTAS);
-            let idx = (magic.factor.wrapping_mul(subset) >> (64 - 12)) as usize + magic.offset;
+            let idx = (magic.factor.wrapping_mul(subset) >> magic.shift) as usize + magic.offset;
             assert!(table[idx] == 0 || table[idx] == attack);
             table[idx] = attack;
             subset = subset.wrapping_sub(range) & range;
@@ -85,23 +103,96 @@ const fn bootstrap_magics() -> [u64; 88772] {
     }
     table
 }
-static ATTACKS: [u64; 88772] = bootstrap_magics();
-pub const fn rook_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+static ATTACKS: [u64; MAGIC_TABLE_SIZE] = bootstrap_magics();
+pub const fn rook_attacks_magic(sq: Square, occupied: BitBoard) -> BitBoard {
     let magic = &ROOK_MAGICS[sq.index()];
-    let idx =
-        ((occupied & magic.mask).wrapping_mul(magic.factor) >> (64 - 12)) as usize + magic.offset;
-    ATTACKS[idx]
+    let idx = ((occupied.0 & magic.mask).wrapping_mul(magic.factor) >> magic.shift) as usize
+        + magic.offset;
+    BitBoard(ATTACKS[idx])
 }
-pub const fn bishop_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+pub const fn bishop_attacks_magic(sq: Square, occupied: BitBoard) -> BitBoard {
     let magic = &BISHOP_MAGICS[sq.index()];
-    let idx =
-        ((occupied & magic.mask).wrapping_mul(magic.factor) >> (64 - 9)) as usize + magic.offset;
-    ATTACKS[idx]
+    let idx = ((occupied.0 & magic.mask).wrapping_mul(magic.factor) >> magic.shift) as usize
+        + magic.offset;
+    BitBoard(ATTACKS[idx])
 }
-pub const fn queen_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+pub const fn queen_attacks_magic(sq: Square, occupied: BitBoard) -> BitBoard {
+    rook_attacks_magic(sq, occupied).symmetric_difference(bishop_attacks_magic(sq, occupied))
+}
+pub const fn attacks_magic(sq: Square, piece: Piece, occupied: BitBoard) -> BitBoard {
+    match piece.piecetype() {
+        PieceType::Pawn => pawn_attacks(piece.col(), sq),
+        PieceType::Knight => knight_attacks(sq),
+        PieceType::King => king_attacks(sq),
+        PieceType::Rook => rook_attacks_magic(sq, occupied),
+        PieceType::Bishop => bishop_attacks_magic(sq, occupied),
+        PieceType::Queen => queen_attacks_magic(sq, occupied),
+    }
+}
+
+// The magic lookups above stay usable in const contexts; everything else
+// goes through the functions below, which use the PEXT tables instead when
+// the `pext` feature is enabled on a BMI2 target.
+/// Slider attacks stop at, and include, the first occupied square in each
+/// direction, whatever its colour.
+///
+/// ```
+/// use tuatara::{attacks, BB_EMPTY, Square};
+///
+/// assert_eq!(attacks::rook_attacks(Square::A1, BB_EMPTY).popcount(), 14);
+/// let blocked = attacks::rook_attacks(Square::A1, Square::A2.as_bb() | Square::B1.as_bb());
+/// assert_eq!(blocked, Square::A2.as_bb() | Square::B1.as_bb());
+/// ```
+#[inline]
+pub fn rook_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+    #[cfg(all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2"))]
+    {
+        crate::pext::rook_attacks(sq, occupied)
+    }
+    #[cfg(not(all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2")))]
+    {
+        rook_attacks_magic(sq, occupied)
+    }
+}
+/// ```
+/// use tuatara::{attacks, BB_EMPTY, Square};
+///
+/// assert_eq!(attacks::bishop_attacks(Square::D4, BB_EMPTY).popcount(), 13);
+/// assert_eq!(attacks::bishop_attacks(Square::A1, Square::B2.as_bb()), Square::B2.as_bb());
+/// ```
+#[inline]
+pub fn bishop_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+    #[cfg(all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2"))]
+    {
+        crate::pext::bishop_attacks(sq, occupied)
+    }
+    #[cfg(not(all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2")))]
+    {
+        bishop_attacks_magic(sq, occupied)
+    }
+}
+/// ```
+/// use tuatara::{attacks, BB_EMPTY, Square};
+///
+/// let queen = attacks::queen_attacks(Square::D4, BB_EMPTY);
+/// let rook = attacks::rook_attacks(Square::D4, BB_EMPTY);
+/// let bishop = attacks::bishop_attacks(Square::D4, BB_EMPTY);
+/// assert_eq!(queen, rook | bishop);
+/// ```
+pub fn queen_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
     rook_attacks(sq, occupied) ^ bishop_attacks(sq, occupied)
 }
-pub const fn attacks(sq: Square, piece: Piece, occupied: BitBoard) -> BitBoard {
+/// The attacks of any piece, dispatching on its type.
+///
+/// ```
+/// use tuatara::{attacks, Board, Piece, Square};
+///
+/// let board = Board::new();
+/// let knight = attacks::attacks(Square::G1, Piece::WN, board.occupied());
+/// assert_eq!(knight, Square::E2.as_bb() | Square::F3.as_bb() | Square::H3.as_bb());
+/// assert_eq!(attacks::attacks(Square::A1, Piece::WR, board.occupied()).popcount(), 2);
+/// ```
+pub fn attacks(sq: Square, piece: Piece, occupied: BitBoard) -> BitBoard {
     match piece.piecetype() {
         PieceType::Pawn => pawn_attacks(piece.col(), sq),
         PieceType::Knight => knight_attacks(sq),
@@ -119,69 +210,111 @@ mod tests {
 
     #[test]
     fn pawn_attacks() {
-        assert_eq!(attacks::pawn_attacks(Col::White, Square::A2), 131072);
-        assert_eq!(attacks::pawn_attacks(Col::White, Square::B2), 327680);
-        assert_eq!(attacks::pawn_attacks(Col::Black, Square::A7), 2199023255552);
-        assert_eq!(attacks::pawn_attacks(Col::Black, Square::B7), 5497558138880);
+        assert_eq!(
+            attacks::pawn_attacks(Col::White, Square::A2),
+            BitBoard(131072)
+        );
+        assert_eq!(
+            attacks::pawn_attacks(Col::White, Square::B2),
+            BitBoard(327680)
+        );
+        assert_eq!(
+            attacks::pawn_attacks(Col::Black, Square::A7),
+            BitBoard(2199023255552)
+        );
+        assert_eq!(
+            attacks::pawn_attacks(Col::Black, Square::B7),
+            BitBoard(5497558138880)
+        );
     }
     #[test]
     fn knight_attacks() {
-        assert_eq!(attacks::knight_attacks(Square::A1), 132096);
-        assert_eq!(attacks::knight_attacks(Square::D1), 1319424);
-        assert_eq!(attacks::knight_attacks(Square::E1), 2638848);
-        assert_eq!(attacks::knight_attacks(Square::H1), 4202496);
+        assert_eq!(attacks::knight_attacks(Square::A1), BitBoard(132096));
+        assert_eq!(attacks::knight_attacks(Square::D1), BitBoard(1319424));
+        assert_eq!(attacks::knight_attacks(Square::E1), BitBoard(2638848));
+        assert_eq!(attacks::knight_attacks(Square::H1), BitBoard(4202496));
     }
     #[test]
     fn king_attacks() {
-        assert_eq!(attacks::king_attacks(Square::A1), 770);
-        assert_eq!(attacks::king_attacks(Square::D1), 7188);
-        assert_eq!(attacks::king_attacks(Square::E1), 14376);
-        assert_eq!(attacks::king_attacks(Square::H1), 49216);
+        assert_eq!(attacks::king_attacks(Square::A1), BitBoard(770));
+        assert_eq!(attacks::king_attacks(Square::D1), BitBoard(7188));
+        assert_eq!(attacks::king_attacks(Square::E1), BitBoard(14376));
+        assert_eq!(attacks::king_attacks(Square::H1), BitBoard(49216));
     }
     #[test]
     fn rook_attacks() {
-        let occupied = 0;
+        let occupied = BB_EMPTY;
         assert_eq!(
             attacks::rook_attacks(Square::A1, occupied),
-            72340172838076926
+            BitBoard(72340172838076926)
+        );
+        assert_eq!(
+            attacks::rook_attacks(Square::A1, BitBoard(4)),
+            BitBoard(72340172838076678)
+        );
+        assert_eq!(
+            attacks::rook_attacks(Square::A1, BitBoard(65540)),
+            BitBoard(65798)
+        );
+        assert_eq!(
+            attacks::rook_attacks(Square::A1, BitBoard(258)),
+            BitBoard(258)
         );
-        assert_eq!(attacks::rook_attacks(Square::A1, 4), 72340172838076678);
-        assert_eq!(attacks::rook_attacks(Square::A1, 65540), 65798);
-        assert_eq!(attacks::rook_attacks(Square::A1, 258), 258);
     }
     #[test]
     fn bishop_attacks() {
-        let occupied = 0;
-        assert_eq!(attacks::bishop_attacks(Square::D1, occupied), 550848566272);
-        assert_eq!(attacks::bishop_attacks(Square::D1, 131072), 550831789056);
-        assert_eq!(attacks::bishop_attacks(Square::D1, 2228224), 2233344);
-        assert_eq!(attacks::bishop_attacks(Square::D1, 5120), 5120);
+        let occupied = BB_EMPTY;
+        assert_eq!(
+            attacks::bishop_attacks(Square::D1, occupied),
+            BitBoard(550848566272)
+        );
+        assert_eq!(
+            attacks::bishop_attacks(Square::D1, BitBoard(131072)),
+            BitBoard(550831789056)
+        );
+        assert_eq!(
+            attacks::bishop_attacks(Square::D1, BitBoard(2228224)),
+            BitBoard(2233344)
+        );
+        assert_eq!(
+            attacks::bishop_attacks(Square::D1, BitBoard(5120)),
+            BitBoard(5120)
+        );
     }
     #[test]
     fn queen_attacks() {
-        let occupied = 0;
+        let occupied = BB_EMPTY;
         assert_eq!(
             attacks::queen_attacks(Square::A1, occupied),
-            9313761861428380670
+            BitBoard(9313761861428380670)
         );
         assert_eq!(
-            attacks::queen_attacks(Square::A1, 65536),
-            9241421688590369790
+            attacks::queen_attacks(Square::A1, BitBoard(65536)),
+            BitBoard(9241421688590369790)
+        );
+        assert_eq!(
+            attacks::queen_attacks(Square::A1, BitBoard(327680)),
+            BitBoard(328702)
+        );
+        assert_eq!(
+            attacks::queen_attacks(Square::A1, BitBoard(327684)),
+            BitBoard(328454)
+        );
+        assert_eq!(
+            attacks::queen_attacks(Square::A1, BitBoard(770)),
+            BitBoard(770)
         );
-        assert_eq!(attacks::queen_attacks(Square::A1, 327680), 328702);
-        assert_eq!(attacks::queen_attacks(Square::A1, 327684), 328454);
-        assert_eq!(attacks::queen_attacks(Square::A1, 770), 770);
     }
     #[test]
     fn attacks() {
-        let occupied = 0;
+        let occupied = BB_EMPTY;
         assert_eq!(
             attacks::attacks(
                 Square::A1,
                 Piece::new(Col::White, PieceType::Pawn),
                 occupied
             ),
-            512
+            BitBoard(512)
         );
         assert_eq!(
             attacks::attacks(
@@ -189,7 +322,7 @@ mod tests {
                 Piece::new(Col::White, PieceType::Knight),
                 occupied
             ),
-            132096
+            BitBoard(132096)
         );
         assert_eq!(
             attacks::attacks(
@@ -197,7 +330,7 @@ mod tests {
                 Piece::new(Col::White, PieceType::King),
                 occupied
             ),
-            770
+            BitBoard(770)
         );
         assert_eq!(
             attacks::attacks(
@@ -205,7 +338,7 @@ mod tests {
                 Piece::new(Col::White, PieceType::Rook),
                 occupied
             ),
-            72340172838076926
+            BitBoard(72340172838076926)
         );
         assert_eq!(
             attacks::attacks(
@@ -213,7 +346,7 @@ mod tests {
                 Piece::new(Col::White, PieceType::Bishop),
                 occupied
             ),
-            9241421688590303744
+            BitBoard(9241421688590303744)
         );
         assert_eq!(
             attacks::attacks(
@@ -221,7 +354,15 @@ mod tests {
                 Piece::new(Col::White, PieceType::Queen),
                 occupied
             ),
-            9313761861428380670
+            BitBoard(9313761861428380670)
         );
     }
+    // the magic lookups stay const whatever features are enabled
+    #[test]
+    fn const_attacks() {
+        const ROOK: BitBoard = attacks::rook_attacks_magic(Square::A1, BB_EMPTY);
+        const QUEEN: BitBoard = attacks::attacks_magic(Square::A1, Piece::WQ, BB_EMPTY);
+        assert_eq!(ROOK, BitBoard(72340172838076926));
+        assert_eq!(QUEEN, BitBoard(9313761861428380670));
+    }
 }
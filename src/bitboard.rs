@@ -1,96 +1,368 @@
-use std::fmt::Write;
+use std::fmt::{self, Display};
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, Shr, Sub, SubAssign,
+};
 
 use crate::consts::*;
 
-pub const fn contains(bb: BitBoard, other: BitBoard) -> bool {
-    (bb & other) == other
-}
-pub const fn contains_square(bb: BitBoard, square: Square) -> bool {
-    let mask = 1u64 << square.index();
-    (bb & mask) != 0
-}
-pub const fn add_square(bb: BitBoard, square: Square) -> BitBoard {
-    let mask = 1u64 << square.index();
-    bb | mask
-}
-pub const fn remove_square(bb: BitBoard, square: Square) -> BitBoard {
-    let mask = !(1u64 << square.index());
-    bb & mask
-}
-pub const fn toggle_square(bb: BitBoard, square: Square) -> BitBoard {
-    let mask = 1u64 << square.index();
-    bb ^ mask
+impl BitBoard {
+    pub const EMPTY: Self = BitBoard(0);
+    pub const FULL: Self = BitBoard(!0);
+
+    pub const fn new(bits: u64) -> Self {
+        BitBoard(bits)
+    }
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+    pub const fn from_square(square: Square) -> Self {
+        BitBoard(1u64 << square.index())
+    }
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+    pub const fn contains(self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+    pub const fn contains_square(self, square: Square) -> bool {
+        self.0 & (1u64 << square.index()) != 0
+    }
+    #[must_use]
+    pub const fn add_square(self, square: Square) -> Self {
+        BitBoard(self.0 | 1u64 << square.index())
+    }
+    #[must_use]
+    pub const fn remove_square(self, square: Square) -> Self {
+        BitBoard(self.0 & !(1u64 << square.index()))
+    }
+    #[must_use]
+    pub const fn toggle_square(self, square: Square) -> Self {
+        BitBoard(self.0 ^ 1u64 << square.index())
+    }
+    pub const fn union(self, other: Self) -> Self {
+        BitBoard(self.0 | other.0)
+    }
+    pub const fn intersection(self, other: Self) -> Self {
+        BitBoard(self.0 & other.0)
+    }
+    pub const fn difference(self, other: Self) -> Self {
+        BitBoard(self.0 & !other.0)
+    }
+    pub const fn symmetric_difference(self, other: Self) -> Self {
+        BitBoard(self.0 ^ other.0)
+    }
+    pub const fn complement(self) -> Self {
+        BitBoard(!self.0)
+    }
+    pub const fn popcount(self) -> u32 {
+        self.0.count_ones()
+    }
+    pub const fn lsb(self) -> Option<Square> {
+        if self.0 == 0 {
+            return None;
+        }
+        // SAFETY: u64::trailing_zeros is below 64 for a non-zero value,
+        // all of which correspond to valid enum variants of Square.
+        Some(unsafe { Square::new_unchecked(self.0.trailing_zeros() as u8) })
+    }
+    pub const fn msb(self) -> Option<Square> {
+        if self.0 == 0 {
+            return None;
+        }
+        // SAFETY: u64::leading_zeros is below 64 for a non-zero value,
+        // so 63 - leading_zeros is a valid enum variant of Square.
+        Some(unsafe { Square::new_unchecked(63 - self.0.leading_zeros() as u8) })
+    }
+    pub const fn iter(self) -> SquareIter {
+        SquareIter::new(self)
+    }
+    pub const fn north_east_one(self) -> Self {
+        BitBoard((self.0 << 9) & !BB_FILE_A.0)
+    }
+    pub const fn north_west_one(self) -> Self {
+        BitBoard((self.0 << 7) & !BB_FILE_H.0)
+    }
+    pub const fn south_east_one(self) -> Self {
+        BitBoard((self.0 >> 7) & !BB_FILE_A.0)
+    }
+    pub const fn south_west_one(self) -> Self {
+        BitBoard((self.0 >> 9) & !BB_FILE_H.0)
+    }
+    pub const fn east_one(self) -> Self {
+        BitBoard((self.0 << 1) & !BB_FILE_A.0)
+    }
+    pub const fn west_one(self) -> Self {
+        BitBoard((self.0 >> 1) & !BB_FILE_H.0)
+    }
+    pub const fn north_one(self) -> Self {
+        BitBoard(self.0 << 8)
+    }
+    pub const fn south_one(self) -> Self {
+        BitBoard(self.0 >> 8)
+    }
+    #[must_use]
+    pub const fn isolate_lsb(self) -> Self {
+        BitBoard(self.0 & 0u64.wrapping_sub(self.0))
+    }
+    #[must_use]
+    pub const fn without_lsb(self) -> Self {
+        BitBoard(self.0 & self.0.wrapping_sub(1))
+    }
+    pub const fn one(self) -> bool {
+        self.0 != 0 && self.without_lsb().0 == 0
+    }
+    pub const fn many(self) -> bool {
+        self.without_lsb().0 != 0
+    }
+    // Rank 1 <-> rank 8.
+    #[must_use]
+    pub const fn flip_vertical(self) -> Self {
+        BitBoard(self.0.swap_bytes())
+    }
+    // File a <-> file h.
+    #[must_use]
+    pub const fn mirror_horizontal(self) -> Self {
+        const K1: u64 = 0x5555_5555_5555_5555;
+        const K2: u64 = 0x3333_3333_3333_3333;
+        const K4: u64 = 0x0f0f_0f0f_0f0f_0f0f;
+        let mut x = self.0;
+        x = ((x >> 1) & K1) | ((x & K1) << 1);
+        x = ((x >> 2) & K2) | ((x & K2) << 2);
+        x = ((x >> 4) & K4) | ((x & K4) << 4);
+        BitBoard(x)
+    }
+    // Reflection about the a1-h8 diagonal.
+    #[must_use]
+    pub const fn flip_diagonal(self) -> Self {
+        const K1: u64 = 0x5500_5500_5500_5500;
+        const K2: u64 = 0x3333_0000_3333_0000;
+        const K4: u64 = 0x0f0f_0f0f_0000_0000;
+        let mut x = self.0;
+        let mut t = K4 & (x ^ (x << 28));
+        x ^= t ^ (t >> 28);
+        t = K2 & (x ^ (x << 14));
+        x ^= t ^ (t >> 14);
+        t = K1 & (x ^ (x << 7));
+        x ^= t ^ (t >> 7);
+        BitBoard(x)
+    }
+    // Reflection about the a8-h1 diagonal.
+    #[must_use]
+    pub const fn flip_anti_diagonal(self) -> Self {
+        const K1: u64 = 0xaa00_aa00_aa00_aa00;
+        const K2: u64 = 0xcccc_0000_cccc_0000;
+        const K4: u64 = 0xf0f0_f0f0_0f0f_0f0f;
+        let mut x = self.0;
+        let mut t = x ^ (x << 36);
+        x ^= K4 & (t ^ (x >> 36));
+        t = K2 & (x ^ (x << 18));
+        x ^= t ^ (t >> 18);
+        t = K1 & (x ^ (x << 9));
+        x ^= t ^ (t >> 9);
+        BitBoard(x)
+    }
+    #[must_use]
+    pub const fn rotate_180(self) -> Self {
+        BitBoard(self.0.reverse_bits())
+    }
+    #[must_use]
+    pub const fn rotate_90_clockwise(self) -> Self {
+        self.flip_diagonal().flip_vertical()
+    }
+    #[must_use]
+    pub const fn rotate_90_anticlockwise(self) -> Self {
+        self.flip_vertical().flip_diagonal()
+    }
+    // Kogge-Stone occluded fills: every square reachable from a square of
+    // self by sliding through `empty`, including the starting squares.
+    // Shift the result one step in the same direction for sliding attacks.
+    pub const fn north_occluded(self, empty: Self) -> Self {
+        BitBoard(occluded_fill(self.0, empty.0, 8, BB_FULL.0))
+    }
+    pub const fn south_occluded(self, empty: Self) -> Self {
+        BitBoard(occluded_fill(self.0, empty.0, -8, BB_FULL.0))
+    }
+    pub const fn east_occluded(self, empty: Self) -> Self {
+        BitBoard(occluded_fill(self.0, empty.0, 1, !BB_FILE_A.0))
+    }
+    pub const fn west_occluded(self, empty: Self) -> Self {
+        BitBoard(occluded_fill(self.0, empty.0, -1, !BB_FILE_H.0))
+    }
+    pub const fn north_east_occluded(self, empty: Self) -> Self {
+        BitBoard(occluded_fill(self.0, empty.0, 9, !BB_FILE_A.0))
+    }
+    pub const fn north_west_occluded(self, empty: Self) -> Self {
+        BitBoard(occluded_fill(self.0, empty.0, 7, !BB_FILE_H.0))
+    }
+    pub const fn south_east_occluded(self, empty: Self) -> Self {
+        BitBoard(occluded_fill(self.0, empty.0, -7, !BB_FILE_A.0))
+    }
+    pub const fn south_west_occluded(self, empty: Self) -> Self {
+        BitBoard(occluded_fill(self.0, empty.0, -9, !BB_FILE_H.0))
+    }
+    // Unobstructed fills, including the starting squares.
+    pub const fn north_fill(self) -> Self {
+        let mut x = self.0;
+        x |= x << 8;
+        x |= x << 16;
+        x |= x << 32;
+        BitBoard(x)
+    }
+    pub const fn south_fill(self) -> Self {
+        let mut x = self.0;
+        x |= x >> 8;
+        x |= x >> 16;
+        x |= x >> 32;
+        BitBoard(x)
+    }
+    pub const fn file_fill(self) -> Self {
+        self.north_fill().union(self.south_fill())
+    }
+    // Squares in front of each square from col's point of view,
+    // excluding the squares themselves.
+    pub const fn front_span(self, col: Col) -> Self {
+        match col {
+            Col::White => self.north_fill().north_one(),
+            Col::Black => self.south_fill().south_one(),
+        }
+    }
+    pub const fn rear_span(self, col: Col) -> Self {
+        self.front_span(col.flip())
+    }
+    // Squares a pawn of col on each square could ever attack while
+    // advancing: the front span of the two adjacent files.
+    pub const fn attack_span(self, col: Col) -> Self {
+        let front = self.front_span(col);
+        front.east_one().union(front.west_one())
+    }
 }
-pub fn iter(bb: BitBoard) -> SquareIter {
-    SquareIter::new(bb)
+
+// One direction of a Kogge-Stone fill. `wrap` removes the squares that a
+// shift by `shift` would bring in from the opposite edge of the board.
+const fn occluded_fill(generator: u64, empty: u64, shift: i32, wrap: u64) -> u64 {
+    const fn shifted(x: u64, shift: i32) -> u64 {
+        if shift > 0 { x << shift } else { x >> -shift }
+    }
+    let mut generator = generator;
+    let mut propagator = empty & wrap;
+    generator |= propagator & shifted(generator, shift);
+    propagator &= shifted(propagator, shift);
+    generator |= propagator & shifted(generator, 2 * shift);
+    propagator &= shifted(propagator, 2 * shift);
+    generator |= propagator & shifted(generator, 4 * shift);
+    generator
 }
-pub const fn first(bb: BitBoard) -> Square {
-    if bb == 0 {
-        return Square::A1; // or any default square
+impl Display for BitBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for rank in Rank::all().rev() {
+            for file in File::all() {
+                let c = if self.contains_square(Square::from_rank_file(rank, file)) {
+                    '1'
+                } else {
+                    '0'
+                };
+                write!(f, "{c} ")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
     }
-    // SAFETY: u64::trailing_zeros can only return values within `0..64`,
-    // all of which correspond to valid enum variants of Square.
-    unsafe { Square::new_unchecked(bb.trailing_zeros() as u8) }
 }
-pub const fn from_square(square: Square) -> BitBoard {
-    1u64 << square.index()
+impl From<Square> for BitBoard {
+    fn from(square: Square) -> Self {
+        Self::from_square(square)
+    }
 }
-pub fn north_east_one(bb: BitBoard) -> BitBoard {
-    (bb << 9) & !BB_FILE_A
+impl BitAnd for BitBoard {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
 }
-pub fn north_west_one(bb: BitBoard) -> BitBoard {
-    (bb << 7) & !BB_FILE_H
+impl BitOr for BitBoard {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
 }
-pub fn south_east_one(bb: BitBoard) -> BitBoard {
-    (bb >> 7) & !BB_FILE_A
+impl BitXor for BitBoard {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        self.symmetric_difference(rhs)
+    }
 }
-pub fn south_west_one(bb: BitBoard) -> BitBoard {
-    (bb >> 9) & !BB_FILE_H
+impl Sub for BitBoard {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.difference(rhs)
+    }
 }
-pub fn east_one(bb: BitBoard) -> BitBoard {
-    (bb << 1) & !BB_FILE_A
+impl Not for BitBoard {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        self.complement()
+    }
 }
-pub fn west_one(bb: BitBoard) -> BitBoard {
-    (bb >> 1) & !BB_FILE_H
+impl Shl<u32> for BitBoard {
+    type Output = Self;
+
+    fn shl(self, rhs: u32) -> Self::Output {
+        BitBoard(self.0 << rhs)
+    }
 }
-pub fn north_one(bb: BitBoard) -> BitBoard {
-    bb << 8
+impl Shr<u32> for BitBoard {
+    type Output = Self;
+
+    fn shr(self, rhs: u32) -> Self::Output {
+        BitBoard(self.0 >> rhs)
+    }
 }
-pub fn south_one(bb: BitBoard) -> BitBoard {
-    bb >> 8
+impl BitAndAssign for BitBoard {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
 }
-pub fn isolate_lsb(bb: BitBoard) -> BitBoard {
-    bb & 0u64.wrapping_sub(bb)
+impl BitOrAssign for BitBoard {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
 }
-pub fn without_lsb(bb: BitBoard) -> BitBoard {
-    bb & bb.wrapping_sub(1)
+impl BitXorAssign for BitBoard {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.0 ^= rhs.0;
+    }
 }
-pub fn one(bb: BitBoard) -> bool {
-    bb != BB_EMPTY && without_lsb(bb) == BB_EMPTY
+impl SubAssign for BitBoard {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 &= !rhs.0;
+    }
 }
-pub fn many(bb: BitBoard) -> bool {
-    without_lsb(bb) != BB_EMPTY
+impl IntoIterator for BitBoard {
+    type Item = Square;
+    type IntoIter = SquareIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
-pub fn to_string(bb: BitBoard) -> String {
-    let mut s = String::with_capacity(136);
-    for rank in 0..8 {
-        for file in (0..8).rev() {
-            let mask = 1u64 << (63 - (rank * 8) - file);
-            let char = if bb & mask != 0 { '1' } else { '0' };
-            s.write_char(char).unwrap();
-            s.write_char(' ').unwrap();
-        }
-        s.write_char('\n').unwrap();
+impl FromIterator<Square> for BitBoard {
+    fn from_iter<I: IntoIterator<Item = Square>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(BitBoard::EMPTY, |bb, square| bb.add_square(square))
     }
-    s
 }
 
 // Iterator over the squares of a square-set.
 // The squares are returned in increasing order.
 pub struct SquareIter(u64);
 impl SquareIter {
-    pub const fn new(value: u64) -> Self {
-        Self(value)
+    pub const fn new(bb: BitBoard) -> Self {
+        Self(bb.0)
     }
 }
 impl Iterator for SquareIter {
@@ -109,173 +381,384 @@ impl Iterator for SquareIter {
             Some(unsafe { Square::new_unchecked(lsb) })
         }
     }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
 }
+impl ExactSizeIterator for SquareIter {}
 
 #[cfg(test)]
 mod tests {
-    use crate::{bitboard, bitboard::BitBoard, consts::*};
+    use crate::attacks;
+    use crate::consts::*;
     #[test]
     fn to_string() {
-        let bbstr = bitboard::to_string(1);
+        let bbstr = BitBoard(1).to_string();
         let bbstrlen = bbstr.len();
         assert_eq!(bbstrlen, 136);
-        assert_eq!(bbstr.starts_with("0 0 0 0 0 0 0 0 \n"), true);
-        assert_eq!(bbstr.ends_with("1 0 0 0 0 0 0 0 \n"), true);
+        assert!(bbstr.starts_with("0 0 0 0 0 0 0 0 \n"));
+        assert!(bbstr.ends_with("1 0 0 0 0 0 0 0 \n"));
+        let bbstr = BB_RANK_8.to_string();
+        assert!(bbstr.starts_with("1 1 1 1 1 1 1 1 \n0 0"));
     }
     #[test]
     fn empty() {
         let empty: BitBoard = BB_EMPTY;
-        assert_eq!(empty, 0);
-        assert!(!bitboard::one(empty));
-        assert!(!bitboard::many(empty));
+        assert_eq!(empty, BitBoard(0));
+        assert_eq!(empty, BitBoard::EMPTY);
+        assert!(empty.is_empty());
+        assert!(!empty.one());
+        assert!(!empty.many());
     }
     #[test]
     fn full() {
         let full = BB_FULL;
-        assert_eq!(full, 18446744073709551615);
-        assert!(!bitboard::one(full));
-        assert!(bitboard::many(full));
+        assert_eq!(full.bits(), 18446744073709551615);
+        assert_eq!(full, BitBoard::FULL);
+        assert!(!full.is_empty());
+        assert!(!full.one());
+        assert!(full.many());
+    }
+    #[test]
+    fn contains() {
+        assert!(BB_RANK_1.contains(BitBoard::from_square(Square::C1)));
+        assert!(BB_RANK_1.contains(BB_EMPTY));
+        assert!(!BB_RANK_1.contains(BB_FILE_A));
+        assert!(BB_FILE_A.contains_square(Square::A5));
+        assert!(!BB_FILE_A.contains_square(Square::B5));
     }
     #[test]
     fn north_east_one() {
-        let mut bb = bitboard::from_square(Square::A1);
-        let mut ne_bb = bitboard::north_east_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::B2));
-        bb = bitboard::from_square(Square::B5);
-        ne_bb = bitboard::north_east_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::C6));
-        bb = bitboard::from_square(Square::H4);
-        ne_bb = bitboard::north_east_one(bb);
+        let mut bb = BitBoard::from_square(Square::A1);
+        let mut ne_bb = bb.north_east_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::B2));
+        bb = BitBoard::from_square(Square::B5);
+        ne_bb = bb.north_east_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::C6));
+        bb = BitBoard::from_square(Square::H4);
+        ne_bb = bb.north_east_one();
         assert_eq!(ne_bb, BB_EMPTY);
-        bb = bitboard::from_square(Square::G8);
-        ne_bb = bitboard::north_east_one(bb);
+        bb = BitBoard::from_square(Square::G8);
+        ne_bb = bb.north_east_one();
         assert_eq!(ne_bb, BB_EMPTY);
     }
     #[test]
     fn north_west_one() {
-        let mut bb = bitboard::from_square(Square::H1);
-        let mut ne_bb = bitboard::north_west_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::G2));
-        bb = bitboard::from_square(Square::B5);
-        ne_bb = bitboard::north_west_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::A6));
-        bb = bitboard::from_square(Square::A4);
-        ne_bb = bitboard::north_west_one(bb);
+        let mut bb = BitBoard::from_square(Square::H1);
+        let mut ne_bb = bb.north_west_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::G2));
+        bb = BitBoard::from_square(Square::B5);
+        ne_bb = bb.north_west_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::A6));
+        bb = BitBoard::from_square(Square::A4);
+        ne_bb = bb.north_west_one();
         assert_eq!(ne_bb, BB_EMPTY);
-        bb = bitboard::from_square(Square::G8);
-        ne_bb = bitboard::north_west_one(bb);
+        bb = BitBoard::from_square(Square::G8);
+        ne_bb = bb.north_west_one();
         assert_eq!(ne_bb, BB_EMPTY);
     }
     #[test]
     fn south_east_one() {
-        let mut bb = bitboard::from_square(Square::A2);
-        let mut ne_bb = bitboard::south_east_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::B1));
-        bb = bitboard::from_square(Square::B5);
-        ne_bb = bitboard::south_east_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::C4));
-        bb = bitboard::from_square(Square::H4);
-        ne_bb = bitboard::south_east_one(bb);
+        let mut bb = BitBoard::from_square(Square::A2);
+        let mut ne_bb = bb.south_east_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::B1));
+        bb = BitBoard::from_square(Square::B5);
+        ne_bb = bb.south_east_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::C4));
+        bb = BitBoard::from_square(Square::H4);
+        ne_bb = bb.south_east_one();
         assert_eq!(ne_bb, BB_EMPTY);
-        bb = bitboard::from_square(Square::G1);
-        ne_bb = bitboard::south_east_one(bb);
+        bb = BitBoard::from_square(Square::G1);
+        ne_bb = bb.south_east_one();
         assert_eq!(ne_bb, BB_EMPTY);
     }
     #[test]
     fn south_west_one() {
-        let mut bb = bitboard::from_square(Square::H2);
-        let mut ne_bb = bitboard::south_west_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::G1));
-        bb = bitboard::from_square(Square::B5);
-        ne_bb = bitboard::south_west_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::A4));
-        bb = bitboard::from_square(Square::A4);
-        ne_bb = bitboard::south_west_one(bb);
+        let mut bb = BitBoard::from_square(Square::H2);
+        let mut ne_bb = bb.south_west_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::G1));
+        bb = BitBoard::from_square(Square::B5);
+        ne_bb = bb.south_west_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::A4));
+        bb = BitBoard::from_square(Square::A4);
+        ne_bb = bb.south_west_one();
         assert_eq!(ne_bb, BB_EMPTY);
-        bb = bitboard::from_square(Square::G1);
-        ne_bb = bitboard::south_west_one(bb);
+        bb = BitBoard::from_square(Square::G1);
+        ne_bb = bb.south_west_one();
         assert_eq!(ne_bb, BB_EMPTY);
     }
     #[test]
     fn east_one() {
-        let mut bb = bitboard::from_square(Square::A2);
-        let mut ne_bb = bitboard::east_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::B2));
-        bb = bitboard::from_square(Square::B5);
-        ne_bb = bitboard::east_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::C5));
-        bb = bitboard::from_square(Square::H4);
-        ne_bb = bitboard::east_one(bb);
+        let mut bb = BitBoard::from_square(Square::A2);
+        let mut ne_bb = bb.east_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::B2));
+        bb = BitBoard::from_square(Square::B5);
+        ne_bb = bb.east_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::C5));
+        bb = BitBoard::from_square(Square::H4);
+        ne_bb = bb.east_one();
         assert_eq!(ne_bb, BB_EMPTY);
     }
     #[test]
     fn west_one() {
-        let mut bb = bitboard::from_square(Square::B2);
-        let mut ne_bb = bitboard::west_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::A2));
-        bb = bitboard::from_square(Square::B5);
-        ne_bb = bitboard::west_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::A5));
-        bb = bitboard::from_square(Square::A4);
-        ne_bb = bitboard::west_one(bb);
+        let mut bb = BitBoard::from_square(Square::B2);
+        let mut ne_bb = bb.west_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::A2));
+        bb = BitBoard::from_square(Square::B5);
+        ne_bb = bb.west_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::A5));
+        bb = BitBoard::from_square(Square::A4);
+        ne_bb = bb.west_one();
         assert_eq!(ne_bb, BB_EMPTY);
     }
     #[test]
     fn north_one() {
-        let mut bb = bitboard::from_square(Square::B2);
-        let mut ne_bb = bitboard::north_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::B3));
-        bb = bitboard::from_square(Square::B5);
-        ne_bb = bitboard::north_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::B6));
-        bb = bitboard::from_square(Square::B8);
-        ne_bb = bitboard::north_one(bb);
+        let mut bb = BitBoard::from_square(Square::B2);
+        let mut ne_bb = bb.north_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::B3));
+        bb = BitBoard::from_square(Square::B5);
+        ne_bb = bb.north_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::B6));
+        bb = BitBoard::from_square(Square::B8);
+        ne_bb = bb.north_one();
         assert_eq!(ne_bb, BB_EMPTY);
     }
     #[test]
     fn south_one() {
-        let mut bb = bitboard::from_square(Square::B2);
-        let mut ne_bb = bitboard::south_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::B1));
-        bb = bitboard::from_square(Square::B5);
-        ne_bb = bitboard::south_one(bb);
-        assert_eq!(ne_bb, bitboard::from_square(Square::B4));
-        bb = bitboard::from_square(Square::B1);
-        ne_bb = bitboard::south_one(bb);
+        let mut bb = BitBoard::from_square(Square::B2);
+        let mut ne_bb = bb.south_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::B1));
+        bb = BitBoard::from_square(Square::B5);
+        ne_bb = bb.south_one();
+        assert_eq!(ne_bb, BitBoard::from_square(Square::B4));
+        bb = BitBoard::from_square(Square::B1);
+        ne_bb = bb.south_one();
         assert_eq!(ne_bb, BB_EMPTY);
     }
     #[test]
     fn isolate_lsb() {
         let bb = BB_FILE_A;
-        let isolated = bitboard::isolate_lsb(bb);
-        assert_eq!(isolated, bitboard::from_square(Square::A1));
+        let isolated = bb.isolate_lsb();
+        assert_eq!(isolated, BitBoard::from_square(Square::A1));
     }
     #[test]
     fn without_lsb() {
-        let bb = bitboard::from_square(Square::A2) | bitboard::from_square(Square::A3);
-        let without_lsb = bitboard::without_lsb(bb);
-        assert_eq!(without_lsb, bitboard::from_square(Square::A3));
+        let bb = BitBoard::from_square(Square::A2) | BitBoard::from_square(Square::A3);
+        let without_lsb = bb.without_lsb();
+        assert_eq!(without_lsb, BitBoard::from_square(Square::A3));
     }
     #[test]
     fn add_square() {
         let one = Square::E4.as_bb();
         assert_ne!(one, BB_EMPTY);
-        assert!(bitboard::one(one));
-        assert!(!bitboard::many(one));
+        assert!(one.one());
+        assert!(!one.many());
 
-        let two = bitboard::add_square(one, Square::E5);
+        let two = one.add_square(Square::E5);
         assert_ne!(two, BB_EMPTY);
-        assert!(!bitboard::one(two));
-        assert!(bitboard::many(two));
+        assert!(!two.one());
+        assert!(two.many());
+        assert_eq!(two.remove_square(Square::E5), one);
+        assert_eq!(two.toggle_square(Square::E4), Square::E5.as_bb());
+        assert_eq!(one.toggle_square(Square::E5), two);
+    }
+    #[test]
+    fn popcount() {
+        assert_eq!(BB_EMPTY.popcount(), 0);
+        assert_eq!(BB_FILE_C.popcount(), 8);
+        assert_eq!(BB_FULL.popcount(), 64);
+    }
+    #[test]
+    fn lsb() {
+        assert_eq!(BB_EMPTY.lsb(), None);
+        assert_eq!(BB_EMPTY.msb(), None);
+        assert_eq!(BB_FILE_C.lsb(), Some(Square::C1));
+        assert_eq!(BB_FILE_C.msb(), Some(Square::C8));
+        assert_eq!(Square::H8.as_bb().lsb(), Some(Square::H8));
+        assert_eq!(Square::A1.as_bb().msb(), Some(Square::A1));
+    }
+    #[test]
+    fn operators() {
+        let a = BB_RANK_1 | BB_RANK_2;
+        assert_eq!(a & BB_FILE_A, Square::A1.as_bb() | Square::A2.as_bb());
+        assert_eq!(a ^ BB_RANK_1, BB_RANK_2);
+        assert_eq!(a - BB_RANK_1, BB_RANK_2);
+        assert_eq!(!BB_EMPTY, BB_FULL);
+        assert_eq!(BB_RANK_1 << 8, BB_RANK_2);
+        assert_eq!(BB_RANK_2 >> 8, BB_RANK_1);
+        let mut b = BB_EMPTY;
+        b |= BB_FILE_A;
+        b &= BB_RANK_1 | BB_RANK_8;
+        assert_eq!(b, Square::A1.as_bb() | Square::A8.as_bb());
+        b ^= Square::A1.as_bb();
+        assert_eq!(b, Square::A8.as_bb());
+        b -= BB_RANK_8;
+        assert_eq!(b, BB_EMPTY);
+        assert_eq!(BB_RANK_1.union(BB_RANK_2), a);
+        assert_eq!(a.intersection(BB_RANK_2), BB_RANK_2);
+        assert_eq!(a.difference(BB_RANK_2), BB_RANK_1);
+        assert_eq!(a.symmetric_difference(BB_RANK_2), BB_RANK_1);
+        assert_eq!(BB_LIGHT_SQUARES.complement(), BB_DARK_SQUARES);
     }
     #[test]
     fn iter() {
         let bb = BB_FILE_A;
-        let mut bb_it = bitboard::iter(bb);
+        let mut bb_it = bb.iter();
+        assert_eq!(bb_it.len(), 8);
         assert_eq!(bb_it.next(), Some(Square::A1));
         assert_eq!(bb_it.next(), Some(Square::A2));
-        bb_it = bitboard::iter(BB_FILE_B);
+        assert_eq!(bb_it.len(), 6);
+        bb_it = BB_FILE_B.iter();
         let bstr: Vec<String> = bb_it.map(|s| s.to_string()).collect();
         assert_eq!(bstr, vec!["b1", "b2", "b3", "b4", "b5", "b6", "b7", "b8"]);
+        let squares: Vec<Square> = BB_RANK_1.into_iter().take(2).collect();
+        assert_eq!(squares, vec![Square::A1, Square::B1]);
+    }
+    #[test]
+    fn from_iter() {
+        let bb: BitBoard = [Square::A1, Square::H8, Square::A1].into_iter().collect();
+        assert_eq!(bb, Square::A1.as_bb() | Square::H8.as_bb());
+        let bb: BitBoard = BB_FILE_D.into_iter().collect();
+        assert_eq!(bb, BB_FILE_D);
+        assert_eq!(BitBoard::from(Square::D4), Square::D4.as_bb());
+    }
+    #[test]
+    fn flips() {
+        let bb = Square::B1.as_bb() | Square::C3.as_bb();
+        assert_eq!(bb.flip_vertical(), Square::B8.as_bb() | Square::C6.as_bb());
+        assert_eq!(
+            bb.mirror_horizontal(),
+            Square::G1.as_bb() | Square::F3.as_bb()
+        );
+        assert_eq!(bb.flip_diagonal(), Square::A2.as_bb() | Square::C3.as_bb());
+        assert_eq!(
+            bb.flip_anti_diagonal(),
+            Square::H7.as_bb() | Square::F6.as_bb()
+        );
+        assert_eq!(BB_RANK_1.flip_vertical(), BB_RANK_8);
+        assert_eq!(BB_FILE_A.mirror_horizontal(), BB_FILE_H);
+        assert_eq!(BB_RANK_1.flip_diagonal(), BB_FILE_A);
+        assert_eq!(BB_RANK_1.flip_anti_diagonal(), BB_FILE_H);
+        for square in Square::all() {
+            let bb = square.as_bb();
+            assert_eq!(bb.flip_vertical().flip_vertical(), bb);
+            assert_eq!(bb.mirror_horizontal().mirror_horizontal(), bb);
+            assert_eq!(bb.flip_diagonal().flip_diagonal(), bb);
+            assert_eq!(bb.flip_anti_diagonal().flip_anti_diagonal(), bb);
+            assert_eq!(bb.flip_vertical(), square.flip_rank().as_bb());
+            assert_eq!(bb.mirror_horizontal(), square.flip_file().as_bb());
+        }
+    }
+    #[test]
+    fn rotations() {
+        let bb = Square::A1.as_bb() | Square::B1.as_bb();
+        assert_eq!(
+            bb.rotate_90_clockwise(),
+            Square::A8.as_bb() | Square::A7.as_bb()
+        );
+        assert_eq!(
+            bb.rotate_90_anticlockwise(),
+            Square::H1.as_bb() | Square::H2.as_bb()
+        );
+        assert_eq!(bb.rotate_180(), Square::H8.as_bb() | Square::G8.as_bb());
+        for square in Square::all() {
+            let bb = square.as_bb();
+            assert_eq!(bb.rotate_90_clockwise().rotate_90_anticlockwise(), bb);
+            assert_eq!(
+                bb.rotate_90_clockwise().rotate_90_clockwise(),
+                bb.rotate_180()
+            );
+            assert_eq!(bb.rotate_180(), bb.flip_vertical().mirror_horizontal());
+        }
+    }
+    #[test]
+    fn occluded() {
+        const DIAGONAL_A1_H8: BitBoard = BitBoard(0x8040_2010_0804_0201);
+        const DIAGONAL_A8_H1: BitBoard = BitBoard(0x0102_0408_1020_4080);
+        let empty = BB_FULL;
+        assert_eq!(Square::A1.as_bb().north_occluded(empty), BB_FILE_A);
+        assert_eq!(Square::H8.as_bb().south_occluded(empty), BB_FILE_H);
+        assert_eq!(Square::A1.as_bb().east_occluded(empty), BB_RANK_1);
+        assert_eq!(Square::H8.as_bb().west_occluded(empty), BB_RANK_8);
+        assert_eq!(
+            Square::A1.as_bb().north_east_occluded(empty),
+            DIAGONAL_A1_H8
+        );
+        assert_eq!(
+            Square::H1.as_bb().north_west_occluded(empty),
+            DIAGONAL_A8_H1
+        );
+        assert_eq!(
+            Square::A8.as_bb().south_east_occluded(empty),
+            DIAGONAL_A8_H1
+        );
+        assert_eq!(
+            Square::H8.as_bb().south_west_occluded(empty),
+            DIAGONAL_A1_H8
+        );
+        // the fill stops on the square before a blocker
+        let empty = !Square::A4.as_bb();
+        assert_eq!(
+            Square::A1.as_bb().north_occluded(empty),
+            Square::A1.as_bb() | Square::A2.as_bb() | Square::A3.as_bb()
+        );
+        // no wrap round from the h-file to the a-file
+        assert_eq!(
+            Square::H3.as_bb().east_occluded(BB_FULL),
+            Square::H3.as_bb()
+        );
+    }
+    #[test]
+    fn occluded_attacks() {
+        let occupied = BB_RANK_2 | BB_RANK_7 | Square::D5.as_bb();
+        let empty = !occupied;
+        for square in Square::all() {
+            let bb = square.as_bb();
+            let rook = bb.north_occluded(empty).north_one()
+                | bb.south_occluded(empty).south_one()
+                | bb.east_occluded(empty).east_one()
+                | bb.west_occluded(empty).west_one();
+            assert_eq!(rook, attacks::rook_attacks(square, occupied));
+            let bishop = bb.north_east_occluded(empty).north_east_one()
+                | bb.north_west_occluded(empty).north_west_one()
+                | bb.south_east_occluded(empty).south_east_one()
+                | bb.south_west_occluded(empty).south_west_one();
+            assert_eq!(bishop, attacks::bishop_attacks(square, occupied));
+        }
+    }
+    #[test]
+    fn fills() {
+        let bb = Square::C3.as_bb();
+        assert_eq!(bb.north_fill(), BB_FILE_C - BB_RANK_1 - BB_RANK_2);
+        assert_eq!(
+            bb.south_fill(),
+            Square::C1.as_bb() | Square::C2.as_bb() | Square::C3.as_bb()
+        );
+        assert_eq!(bb.file_fill(), BB_FILE_C);
+        assert_eq!(
+            (Square::A2.as_bb() | Square::H7.as_bb()).file_fill(),
+            BB_FILE_A | BB_FILE_H
+        );
+    }
+    #[test]
+    fn spans() {
+        let pawn = Square::C6.as_bb();
+        assert_eq!(
+            pawn.front_span(Col::White),
+            Square::C7.as_bb() | Square::C8.as_bb()
+        );
+        assert_eq!(pawn.front_span(Col::Black), pawn.south_fill().south_one());
+        assert_eq!(pawn.rear_span(Col::White), pawn.front_span(Col::Black));
+        assert_eq!(pawn.rear_span(Col::Black), pawn.front_span(Col::White));
+        assert_eq!(
+            pawn.attack_span(Col::White),
+            Square::B7.as_bb() | Square::B8.as_bb() | Square::D7.as_bb() | Square::D8.as_bb()
+        );
+        assert_eq!(
+            Square::A2.as_bb().attack_span(Col::White),
+            BB_FILE_B - BB_RANK_1 - BB_RANK_2
+        );
+        assert_eq!(Square::H1.as_bb().attack_span(Col::Black), BB_EMPTY);
     }
 }
@@ -1,22 +1,48 @@
-use std::fmt;
+use std::fmt::{self, Write};
 
 use crate::attacks;
-use crate::bitboard;
 use crate::consts::*;
 
 impl Board {
+    /// The classical start position.
+    ///
+    /// ```
+    /// use tuatara::{Board, Col, Piece, Square};
+    ///
+    /// let board = Board::new();
+    /// assert_eq!(board.piece_at(Square::D1), Some(Piece::WQ));
+    /// assert_eq!(board.king_of(Col::Black), Some(Square::E8));
+    /// assert_eq!(board.occupied().popcount(), 32);
+    /// ```
     pub const fn new() -> Self {
         Board {
             by_piece: [
-                0x00ff_0000_0000_ff00,
-                0x4200_0000_0000_0042,
-                0x2400_0000_0000_0024,
-                0x8100_0000_0000_0081,
-                0x0800_0000_0000_0008,
-                0x1000_0000_0000_0010,
+                BitBoard(0x00ff_0000_0000_ff00),
+                BitBoard(0x4200_0000_0000_0042),
+                BitBoard(0x2400_0000_0000_0024),
+                BitBoard(0x8100_0000_0000_0081),
+                BitBoard(0x0800_0000_0000_0008),
+                BitBoard(0x1000_0000_0000_0010),
             ],
-            by_col: [0xffff, 0xffff_0000_0000_0000],
-            occupied: 0xffff_0000_0000_ffff,
+            by_col: [BitBoard(0xffff), BitBoard(0xffff_0000_0000_0000)],
+            occupied: BitBoard(0xffff_0000_0000_ffff),
+        }
+    }
+    pub const fn horde() -> Self {
+        Board {
+            by_piece: [
+                BitBoard(0x00ff_0066_ffff_ffff),
+                BitBoard(0x4200_0000_0000_0000),
+                BitBoard(0x2400_0000_0000_0000),
+                BitBoard(0x8100_0000_0000_0000),
+                BitBoard(0x0800_0000_0000_0000),
+                BitBoard(0x1000_0000_0000_0000),
+            ],
+            by_col: [
+                BitBoard(0x0000_0066_ffff_ffff),
+                BitBoard(0xffff_0000_0000_0000),
+            ],
+            occupied: BitBoard(0xffff_0066_ffff_ffff),
         }
     }
     pub const fn empty() -> Self {
@@ -26,33 +52,40 @@ impl Board {
             occupied: BB_EMPTY,
         }
     }
+    /// Builds a board from per-piece-type and per-colour bitboards, which
+    /// must not overlap and must cover the same squares.
+    ///
+    /// ```
+    /// use tuatara::{BB_FULL, Board};
+    ///
+    /// let (by_piece, by_col) = Board::new().into_bitboards();
+    /// assert_eq!(Board::try_from_bitboards(by_piece, by_col), Ok(Board::new()));
+    /// assert!(Board::try_from_bitboards(by_piece, [BB_FULL, BB_FULL]).is_err());
+    /// ```
     pub const fn try_from_bitboards(
         by_piece: [BitBoard; 6],
         by_col: [BitBoard; 2],
-    ) -> Result<Board, &'static str> {
+    ) -> Result<Board, BoardError> {
         let mut occupied = BB_EMPTY;
+        let mut overlap = BB_EMPTY;
         let mut i = 0;
         while i < by_piece.len() {
-            occupied |= by_piece[i];
+            overlap = overlap.union(occupied.intersection(by_piece[i]));
+            occupied = occupied.union(by_piece[i]);
             i += 1;
         }
-
-        let mut sum = 0;
-        let mut j = 0;
-        while j < by_piece.len() {
-            sum += by_piece[j].count_ones();
-            j += 1;
-        }
-        if occupied.count_ones() != sum {
-            return Err("roles overlap");
+        if let Some(square) = overlap.lsb() {
+            return Err(BoardError::RolesOverlap(square));
         }
 
-        if by_col[Col::Black.index()] & by_col[Col::White.index()] != BB_EMPTY {
-            return Err("colours overlap");
+        let black = by_col[Col::Black.index()];
+        let white = by_col[Col::White.index()];
+        if let Some(square) = black.intersection(white).lsb() {
+            return Err(BoardError::ColoursOverlap(square));
         }
 
-        if occupied != by_col[Col::Black.index()] | by_col[Col::White.index()] {
-            return Err("roles and colours are mismatched");
+        if let Some(square) = occupied.symmetric_difference(black.union(white)).lsb() {
+            return Err(BoardError::RolesAndColoursMismatched(square));
         }
 
         Ok(Board {
@@ -92,22 +125,29 @@ impl Board {
         self.by_col[Col::Black.index()]
     }
     pub const fn sliders(&self) -> BitBoard {
-        self.rooks() ^ self.bishops() ^ self.queens()
+        self.rooks()
+            .symmetric_difference(self.bishops())
+            .symmetric_difference(self.queens())
     }
     pub const fn steppers(&self) -> BitBoard {
-        self.knights() ^ self.pawns() ^ self.kings()
+        self.knights()
+            .symmetric_difference(self.pawns())
+            .symmetric_difference(self.kings())
     }
     pub const fn rooks_and_queens(&self) -> BitBoard {
-        self.rooks() ^ self.queens()
+        self.rooks().symmetric_difference(self.queens())
     }
     pub const fn bishops_and_queens(&self) -> BitBoard {
-        self.bishops() ^ self.queens()
+        self.bishops().symmetric_difference(self.queens())
     }
-    pub const fn king_of(&self, colour: Col) -> Square {
-        bitboard::first(self.by_piece[PieceType::King.index()] & self.by_col[colour.index()])
+    // Variants such as horde have a side without a king, so there may be none.
+    pub const fn king_of(&self, colour: Col) -> Option<Square> {
+        self.by_piece[PieceType::King.index()]
+            .intersection(self.by_col[colour.index()])
+            .lsb()
     }
     pub fn col_at(&self, square: Square) -> Option<Col> {
-        let mask = bitboard::from_square(square);
+        let mask = BitBoard::from_square(square);
         if self.by_col[Col::White.index()] & mask != BB_EMPTY {
             Some(Col::White)
         } else if self.by_col[Col::Black.index()] & mask != BB_EMPTY {
@@ -117,7 +157,7 @@ impl Board {
         }
     }
     pub fn piecetype_at(&self, square: Square) -> Option<PieceType> {
-        let mask = bitboard::from_square(square);
+        let mask = BitBoard::from_square(square);
         let mut i = 0;
         while i < self.by_piece.len() {
             let bb = self.by_piece[i];
@@ -140,12 +180,8 @@ impl Board {
         ))
     }
     pub fn remove_piece_at(&mut self, sq: Square) -> Option<Piece> {
-        let piece = self.piece_at(sq);
-        if piece.is_none() {
-            return None;
-        }
-        let piece = piece.unwrap();
-        let mask = bitboard::from_square(sq);
+        let piece = self.piece_at(sq)?;
+        let mask = BitBoard::from_square(sq);
         self.by_piece[piece.piecetype().index()] &= !mask;
         self.by_col[piece.col().index()] &= !mask;
         self.occupied &= !mask;
@@ -157,14 +193,14 @@ impl Board {
             return;
         }
         let piece = piece.unwrap();
-        let mask = bitboard::from_square(sq);
+        let mask = BitBoard::from_square(sq);
         self.by_piece[piece.piecetype().index()] &= !mask;
         self.by_col[piece.col().index()] &= !mask;
         self.occupied &= !mask;
     }
     pub fn set_piece_at(&mut self, sq: Square, piece: Piece) {
         self.discard_piece_at(sq);
-        let mask = bitboard::from_square(sq);
+        let mask = BitBoard::from_square(sq);
         self.by_piece[piece.piecetype().index()] |= mask;
         self.by_col[piece.col().index()] |= mask;
         self.occupied |= mask;
@@ -173,7 +209,7 @@ impl Board {
         if self.piece_at(sq).is_some() {
             panic!("Cannot set a piece at a square that is already occupied");
         }
-        let mask = bitboard::from_square(sq);
+        let mask = BitBoard::from_square(sq);
         self.by_piece[piece.piecetype().index()] |= mask;
         self.by_col[piece.col().index()] |= mask;
         self.occupied |= mask;
@@ -185,7 +221,7 @@ impl Board {
         self.by_piece[piecetype.index()]
     }
     pub const fn by_piece(&self, piece: Piece) -> BitBoard {
-        self.by_piece[piece.piecetype().index()] & self.by_col[piece.col().index()]
+        self.by_piece[piece.piecetype().index()].intersection(self.by_col[piece.col().index()])
     }
     pub fn attacks_from(&self, sq: Square) -> BitBoard {
         let piece = self.piece_at(sq);
@@ -195,6 +231,33 @@ impl Board {
         let piece = piece.unwrap();
         attacks::attacks(sq, piece, self.occupied)
     }
+    pub fn render(&self, perspective: Col) -> String {
+        let mut s = String::new();
+        let ranks: Vec<Rank> = match perspective {
+            Col::White => Rank::all().rev().collect(),
+            Col::Black => Rank::all().collect(),
+        };
+        let files: Vec<File> = match perspective {
+            Col::White => File::all().collect(),
+            Col::Black => File::all().rev().collect(),
+        };
+        for &rank in &ranks {
+            write!(s, "{} ", rank as u8 + 1).unwrap();
+            for &file in &files {
+                let glyph = self
+                    .piece_at(Square::from_rank_file(rank, file))
+                    .map_or('·', Piece::glyph);
+                write!(s, " {glyph}").unwrap();
+            }
+            s.push('\n');
+        }
+        s.push_str("  ");
+        for &file in &files {
+            write!(s, " {}", (b'a' + file as u8) as char).unwrap();
+        }
+        s.push('\n');
+        s
+    }
     pub fn atacks_to(&self, sq: Square, attacker: Col, occupied: BitBoard) -> BitBoard {
         self.by_col(attacker)
             & ((attacks::rook_attacks(sq, occupied) & self.rooks_and_queens())
@@ -205,6 +268,24 @@ impl Board {
     }
 }
 
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RolesOverlap(square) => write!(f, "roles overlap on {square}"),
+            Self::ColoursOverlap(square) => write!(f, "colours overlap on {square}"),
+            Self::RolesAndColoursMismatched(square) => {
+                write!(f, "roles and colours are mismatched on {square}")
+            }
+        }
+    }
+}
+impl std::error::Error for BoardError {}
 impl fmt::Debug for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for rank in Rank::all().rev() {
@@ -226,17 +307,30 @@ impl fmt::Debug for Board {
 #[cfg(test)]
 mod tests {
     use crate::attacks;
-    use crate::bitboard;
     use crate::consts::*;
     #[test]
     fn new() {
         let board = Board::new();
         assert_eq!(
             board.by_piece[PieceType::Pawn.index()],
-            0x00ff_0000_0000_ff00
+            BitBoard(0x00ff_0000_0000_ff00)
+        );
+        assert_eq!(board.by_col[Col::White.index()], BitBoard(0xffff));
+        assert_eq!(board.occupied, BitBoard(0xffff_0000_0000_ffff));
+    }
+    #[test]
+    fn horde() {
+        let board = Board::horde();
+        assert_eq!(board.white().popcount(), 36);
+        assert_eq!(board.by_piece(Piece::WP), board.white());
+        assert_eq!(board.piece_at(Square::B5), Some(Piece::WP));
+        assert_eq!(board.piece_at(Square::D5), None);
+        assert_eq!(board.black(), Board::new().black());
+        let (by_piece, by_col) = board.into_bitboards();
+        assert_eq!(
+            Board::try_from_bitboards(by_piece, by_col),
+            Ok(Board::horde())
         );
-        assert_eq!(board.by_col[Col::White.index()], 0xffff);
-        assert_eq!(board.occupied, 0xffff_0000_0000_ffff);
     }
     #[test]
     fn empty() {
@@ -248,25 +342,28 @@ mod tests {
     #[test]
     fn try_from_bitboards() {
         let mut by_piece = [
-            0x00ff_0000_0000_ff00,
-            0x4200_0000_0000_0042,
-            0x2400_0000_0000_0024,
-            0x8100_0000_0000_0081,
-            0x0800_0000_0000_0008,
-            0x1000_0000_0000_0010,
+            BitBoard(0x00ff_0000_0000_ff00),
+            BitBoard(0x4200_0000_0000_0042),
+            BitBoard(0x2400_0000_0000_0024),
+            BitBoard(0x8100_0000_0000_0081),
+            BitBoard(0x0800_0000_0000_0008),
+            BitBoard(0x1000_0000_0000_0010),
         ];
         let mut by_col = [BB_EMPTY, BB_EMPTY];
         let mut board = Board::try_from_bitboards(by_piece, by_col);
         let err = board.unwrap_err();
-        assert_eq!(err, "roles and colours are mismatched");
+        assert_eq!(err, BoardError::RolesAndColoursMismatched(Square::A1));
+        assert_eq!(err.to_string(), "roles and colours are mismatched on a1");
         by_col = [BB_FULL, BB_RANK_1];
         board = Board::try_from_bitboards(by_piece, by_col);
         let err = board.unwrap_err();
-        assert_eq!(err, "colours overlap");
+        assert_eq!(err, BoardError::ColoursOverlap(Square::A1));
+        assert_eq!(err.to_string(), "colours overlap on a1");
         by_piece[PieceType::Pawn.index()] = BB_FULL;
         board = Board::try_from_bitboards(by_piece, by_col);
         let err = board.unwrap_err();
-        assert_eq!(err, "roles overlap");
+        assert_eq!(err, BoardError::RolesOverlap(Square::A1));
+        assert_eq!(err.to_string(), "roles overlap on a1");
         let nbd = Board::new();
         by_col = nbd.by_col;
         by_piece = nbd.by_piece;
@@ -277,61 +374,66 @@ mod tests {
     fn into_bitboards() {
         let nbd = Board::new();
         let (by_piece, by_col) = nbd.clone().into_bitboards();
-        assert_eq!(by_piece[PieceType::Pawn.index()], 0x00ff_0000_0000_ff00);
-        assert_eq!(by_col[Col::White.index()], 0xffff);
-        assert_eq!(nbd.occupied, 0xffff_0000_0000_ffff);
+        assert_eq!(
+            by_piece[PieceType::Pawn.index()],
+            BitBoard(0x00ff_0000_0000_ff00)
+        );
+        assert_eq!(by_col[Col::White.index()], BitBoard(0xffff));
+        assert_eq!(nbd.occupied, BitBoard(0xffff_0000_0000_ffff));
     }
     #[test]
     fn occupied() {
         let nbd = Board::new();
-        assert_eq!(nbd.occupied(), 0xffff_0000_0000_ffff);
+        assert_eq!(nbd.occupied(), BitBoard(0xffff_0000_0000_ffff));
     }
     #[test]
     fn pawns() {
         let nbd = Board::new();
-        assert_eq!(nbd.pawns(), 0x00ff_0000_0000_ff00);
+        assert_eq!(nbd.pawns(), BitBoard(0x00ff_0000_0000_ff00));
     }
     #[test]
     fn knights() {
         let nbd = Board::new();
-        assert_eq!(nbd.knights(), 0x4200_0000_0000_0042);
+        assert_eq!(nbd.knights(), BitBoard(0x4200_0000_0000_0042));
     }
     #[test]
     fn bishops() {
         let nbd = Board::new();
-        assert_eq!(nbd.bishops(), 0x2400_0000_0000_0024);
+        assert_eq!(nbd.bishops(), BitBoard(0x2400_0000_0000_0024));
     }
     #[test]
     fn rooks() {
         let nbd = Board::new();
-        assert_eq!(nbd.rooks(), 0x8100_0000_0000_0081);
+        assert_eq!(nbd.rooks(), BitBoard(0x8100_0000_0000_0081));
     }
     #[test]
     fn queens() {
         let nbd = Board::new();
-        assert_eq!(nbd.queens(), 0x0800_0000_0000_0008);
+        assert_eq!(nbd.queens(), BitBoard(0x0800_0000_0000_0008));
     }
     #[test]
     fn kings() {
         let nbd = Board::new();
-        assert_eq!(nbd.kings(), 0x1000_0000_0000_0010);
+        assert_eq!(nbd.kings(), BitBoard(0x1000_0000_0000_0010));
     }
     #[test]
     fn white() {
         let nbd = Board::new();
-        assert_eq!(nbd.white(), 0xffff);
+        assert_eq!(nbd.white(), BitBoard(0xffff));
     }
     #[test]
     fn black() {
         let nbd = Board::new();
-        assert_eq!(nbd.black(), 0xffff_0000_0000_0000);
+        assert_eq!(nbd.black(), BitBoard(0xffff_0000_0000_0000));
     }
     #[test]
     fn sliders() {
         let nbd = Board::new();
         assert_eq!(
             nbd.sliders(),
-            0x8100_0000_0000_0081 ^ 0x2400_0000_0000_0024 ^ 0x0800_0000_0000_0008
+            BitBoard(0x8100_0000_0000_0081)
+                ^ BitBoard(0x2400_0000_0000_0024)
+                ^ BitBoard(0x0800_0000_0000_0008)
         );
     }
     #[test]
@@ -339,7 +441,9 @@ mod tests {
         let nbd = Board::new();
         assert_eq!(
             nbd.steppers(),
-            0x4200_0000_0000_0042 ^ 0x00ff_0000_0000_ff00 ^ 0x1000_0000_0000_0010
+            BitBoard(0x4200_0000_0000_0042)
+                ^ BitBoard(0x00ff_0000_0000_ff00)
+                ^ BitBoard(0x1000_0000_0000_0010)
         );
     }
     #[test]
@@ -347,7 +451,7 @@ mod tests {
         let nbd = Board::new();
         assert_eq!(
             nbd.rooks_and_queens(),
-            0x8100_0000_0000_0081 ^ 0x0800_0000_0000_0008
+            BitBoard(0x8100_0000_0000_0081) ^ BitBoard(0x0800_0000_0000_0008)
         );
     }
     #[test]
@@ -355,14 +459,17 @@ mod tests {
         let nbd = Board::new();
         assert_eq!(
             nbd.bishops_and_queens(),
-            0x2400_0000_0000_0024 ^ 0x0800_0000_0000_0008
+            BitBoard(0x2400_0000_0000_0024) ^ BitBoard(0x0800_0000_0000_0008)
         );
     }
     #[test]
     fn king_of() {
         let nbd = Board::new();
-        assert_eq!(nbd.king_of(Col::White), Square::E1);
-        assert_eq!(nbd.king_of(Col::Black), Square::E8);
+        assert_eq!(nbd.king_of(Col::White), Some(Square::E1));
+        assert_eq!(nbd.king_of(Col::Black), Some(Square::E8));
+        let horde = Board::horde();
+        assert_eq!(horde.king_of(Col::White), None);
+        assert_eq!(horde.king_of(Col::Black), Some(Square::E8));
     }
     #[test]
     fn col_at() {
@@ -396,14 +503,14 @@ mod tests {
         assert_eq!(piece, Some(Piece::WR));
         assert_eq!(nbd.piece_at(Square::A1), None);
         assert_eq!(
-            nbd.by_piece[PieceType::Rook.index()] & bitboard::from_square(Square::A1),
+            nbd.by_piece[PieceType::Rook.index()] & BitBoard::from_square(Square::A1),
             BB_EMPTY
         );
         assert_eq!(
-            nbd.by_col[Col::White.index()] & bitboard::from_square(Square::A1),
+            nbd.by_col[Col::White.index()] & BitBoard::from_square(Square::A1),
             BB_EMPTY
         );
-        assert_eq!(nbd.occupied & bitboard::from_square(Square::A1), BB_EMPTY);
+        assert_eq!(nbd.occupied & BitBoard::from_square(Square::A1), BB_EMPTY);
     }
     #[test]
     fn discard_piece_at() {
@@ -411,14 +518,14 @@ mod tests {
         nbd.discard_piece_at(Square::A1);
         assert_eq!(nbd.piece_at(Square::A1), None);
         assert_eq!(
-            nbd.by_piece[PieceType::Rook.index()] & bitboard::from_square(Square::A1),
+            nbd.by_piece[PieceType::Rook.index()] & BitBoard::from_square(Square::A1),
             BB_EMPTY
         );
         assert_eq!(
-            nbd.by_col[Col::White.index()] & bitboard::from_square(Square::A1),
+            nbd.by_col[Col::White.index()] & BitBoard::from_square(Square::A1),
             BB_EMPTY
         );
-        assert_eq!(nbd.occupied & bitboard::from_square(Square::A1), BB_EMPTY);
+        assert_eq!(nbd.occupied & BitBoard::from_square(Square::A1), BB_EMPTY);
     }
     #[test]
     fn set_piece_at() {
@@ -426,16 +533,16 @@ mod tests {
         nbd.set_piece_at(Square::A1, Piece::WR);
         assert_eq!(nbd.piece_at(Square::A1), Some(Piece::WR));
         assert_eq!(
-            nbd.by_piece[PieceType::Rook.index()] & bitboard::from_square(Square::A1),
-            bitboard::from_square(Square::A1)
+            nbd.by_piece[PieceType::Rook.index()] & BitBoard::from_square(Square::A1),
+            BitBoard::from_square(Square::A1)
         );
         assert_eq!(
-            nbd.by_col[Col::White.index()] & bitboard::from_square(Square::A1),
-            bitboard::from_square(Square::A1)
+            nbd.by_col[Col::White.index()] & BitBoard::from_square(Square::A1),
+            BitBoard::from_square(Square::A1)
         );
         assert_eq!(
-            nbd.occupied & bitboard::from_square(Square::A1),
-            bitboard::from_square(Square::A1)
+            nbd.occupied & BitBoard::from_square(Square::A1),
+            BitBoard::from_square(Square::A1)
         );
     }
     #[test]
@@ -444,43 +551,79 @@ mod tests {
         nbd.set_new_piece_at(Square::A1, Piece::WR);
         assert_eq!(nbd.piece_at(Square::A1), Some(Piece::WR));
         assert_eq!(
-            nbd.by_piece[PieceType::Rook.index()] & bitboard::from_square(Square::A1),
-            bitboard::from_square(Square::A1)
+            nbd.by_piece[PieceType::Rook.index()] & BitBoard::from_square(Square::A1),
+            BitBoard::from_square(Square::A1)
         );
         assert_eq!(
-            nbd.by_col[Col::White.index()] & bitboard::from_square(Square::A1),
-            bitboard::from_square(Square::A1)
+            nbd.by_col[Col::White.index()] & BitBoard::from_square(Square::A1),
+            BitBoard::from_square(Square::A1)
         );
         assert_eq!(
-            nbd.occupied & bitboard::from_square(Square::A1),
-            bitboard::from_square(Square::A1)
+            nbd.occupied & BitBoard::from_square(Square::A1),
+            BitBoard::from_square(Square::A1)
         );
     }
     #[test]
     fn by_col() {
         let nbd = Board::new();
-        assert_eq!(nbd.by_col(Col::White), 0xffff);
-        assert_eq!(nbd.by_col(Col::Black), 0xffff_0000_0000_0000);
+        assert_eq!(nbd.by_col(Col::White), BitBoard(0xffff));
+        assert_eq!(nbd.by_col(Col::Black), BitBoard(0xffff_0000_0000_0000));
     }
     #[test]
     fn by_piecetype() {
         let nbd = Board::new();
-        assert_eq!(nbd.by_piecetype(PieceType::Pawn), 0x00ff_0000_0000_ff00);
-        assert_eq!(nbd.by_piecetype(PieceType::Knight), 0x4200_0000_0000_0042);
-        assert_eq!(nbd.by_piecetype(PieceType::Bishop), 0x2400_0000_0000_0024);
-        assert_eq!(nbd.by_piecetype(PieceType::Rook), 0x8100_0000_0000_0081);
-        assert_eq!(nbd.by_piecetype(PieceType::Queen), 0x0800_0000_0000_0008);
-        assert_eq!(nbd.by_piecetype(PieceType::King), 0x1000_0000_0000_0010);
+        assert_eq!(
+            nbd.by_piecetype(PieceType::Pawn),
+            BitBoard(0x00ff_0000_0000_ff00)
+        );
+        assert_eq!(
+            nbd.by_piecetype(PieceType::Knight),
+            BitBoard(0x4200_0000_0000_0042)
+        );
+        assert_eq!(
+            nbd.by_piecetype(PieceType::Bishop),
+            BitBoard(0x2400_0000_0000_0024)
+        );
+        assert_eq!(
+            nbd.by_piecetype(PieceType::Rook),
+            BitBoard(0x8100_0000_0000_0081)
+        );
+        assert_eq!(
+            nbd.by_piecetype(PieceType::Queen),
+            BitBoard(0x0800_0000_0000_0008)
+        );
+        assert_eq!(
+            nbd.by_piecetype(PieceType::King),
+            BitBoard(0x1000_0000_0000_0010)
+        );
     }
     #[test]
     fn by_piece() {
         let nbd = Board::new();
-        assert_eq!(nbd.by_piece(Piece::WP), 0x00ff_0000_0000_ff00 & 0xffff);
-        assert_eq!(nbd.by_piece(Piece::WN), 0x4200_0000_0000_0042 & 0xffff);
-        assert_eq!(nbd.by_piece(Piece::WB), 0x2400_0000_0000_0024 & 0xffff);
-        assert_eq!(nbd.by_piece(Piece::WR), 0x8100_0000_0000_0081 & 0xffff);
-        assert_eq!(nbd.by_piece(Piece::WQ), 0x0800_0000_0000_0008 & 0xffff);
-        assert_eq!(nbd.by_piece(Piece::WK), 0x1000_0000_0000_0010 & 0xffff);
+        assert_eq!(
+            nbd.by_piece(Piece::WP),
+            BitBoard(0x00ff_0000_0000_ff00) & BitBoard(0xffff)
+        );
+        assert_eq!(
+            nbd.by_piece(Piece::WN),
+            BitBoard(0x4200_0000_0000_0042) & BitBoard(0xffff)
+        );
+        assert_eq!(
+            nbd.by_piece(Piece::WB),
+            BitBoard(0x2400_0000_0000_0024) & BitBoard(0xffff)
+        );
+        assert_eq!(
+            nbd.by_piece(Piece::WR),
+            BitBoard(0x8100_0000_0000_0081) & BitBoard(0xffff)
+        );
+        assert_eq!(
+            nbd.by_piece(Piece::WQ),
+            BitBoard(0x0800_0000_0000_0008) & BitBoard(0xffff)
+        );
+        assert_eq!(
+            nbd.by_piece(Piece::WK),
+            BitBoard(0x1000_0000_0000_0010) & BitBoard(0xffff)
+        );
     }
     #[test]
     fn attacks_from() {
@@ -504,17 +647,34 @@ mod tests {
         assert_eq!(nbd.attacks_from(Square::A5), BB_EMPTY);
     }
     #[test]
+    fn render() {
+        let nbd = Board::new();
+        let white = nbd.render(Col::White);
+        assert!(white.starts_with("8  ♜ ♞ ♝ ♛ ♚ ♝ ♞ ♜\n"));
+        assert!(white.contains("\n4  · · · · · · · ·\n"));
+        assert!(white.ends_with("1  ♖ ♘ ♗ ♕ ♔ ♗ ♘ ♖\n   a b c d e f g h\n"));
+        let black = nbd.render(Col::Black);
+        assert!(black.starts_with("1  ♖ ♘ ♗ ♔ ♕ ♗ ♘ ♖\n"));
+        assert!(black.ends_with("8  ♜ ♞ ♝ ♚ ♛ ♝ ♞ ♜\n   h g f e d c b a\n"));
+    }
+    #[test]
     fn atacks_to() {
         let nbd = Board::new();
-        assert_eq!(nbd.atacks_to(Square::A1, Col::White, nbd.occupied), 0);
-        assert_eq!(nbd.atacks_to(Square::E2, Col::White, nbd.occupied), 120);
+        assert_eq!(
+            nbd.atacks_to(Square::A1, Col::White, nbd.occupied),
+            BitBoard(0)
+        );
+        assert_eq!(
+            nbd.atacks_to(Square::E2, Col::White, nbd.occupied),
+            BitBoard(120)
+        );
         assert_eq!(
             nbd.atacks_to(Square::B8, Col::Black, nbd.occupied),
-            72057594037927936
+            BitBoard(72057594037927936)
         );
         assert_eq!(
             nbd.atacks_to(Square::F6, Col::Black, nbd.occupied),
-            4634204016564240384
+            BitBoard(4634204016564240384)
         );
     }
 }
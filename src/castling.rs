@@ -0,0 +1,308 @@
+use std::fmt;
+
+use crate::consts::*;
+
+impl CastlingSide {
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+    pub fn all() -> impl DoubleEndedIterator<Item = Self> {
+        [Self::KingSide, Self::QueenSide].into_iter()
+    }
+}
+
+impl CastlingRights {
+    pub const NONE: Self = CastlingRights {
+        rooks: [[None; 2]; 2],
+    };
+    pub const CLASSICAL: Self = CastlingRights {
+        rooks: [[Some(File::H), Some(File::A)]; 2],
+    };
+    pub const fn rook(&self, col: Col, side: CastlingSide) -> Option<File> {
+        self.rooks[col.index()][side.index()]
+    }
+    pub const fn has(&self, col: Col, side: CastlingSide) -> bool {
+        self.rook(col, side).is_some()
+    }
+    pub const fn is_empty(&self) -> bool {
+        self.rooks[0][0].is_none()
+            && self.rooks[0][1].is_none()
+            && self.rooks[1][0].is_none()
+            && self.rooks[1][1].is_none()
+    }
+    pub const fn set(&mut self, col: Col, side: CastlingSide, file: File) {
+        self.rooks[col.index()][side.index()] = Some(file);
+    }
+    pub const fn remove(&mut self, col: Col, side: CastlingSide) {
+        self.rooks[col.index()][side.index()] = None;
+    }
+    pub const fn remove_col(&mut self, col: Col) {
+        self.rooks[col.index()] = [None; 2];
+    }
+    // Full rights for a start position: the outermost rook on each side of
+    // the king, as long as the king is on its back rank.
+    pub fn from_board(board: &Board) -> Self {
+        let mut rights = Self::NONE;
+        for col in Col::all() {
+            for side in CastlingSide::all() {
+                if let Some(file) = outermost_rook(board, col, side) {
+                    rights.set(col, side, file);
+                }
+            }
+        }
+        rights
+    }
+    // Accepts standard (KQkq), X-FEN and Shredder-FEN castling fields.
+    pub fn from_fen_field(field: &str, board: &Board) -> Result<Self, CastlingError> {
+        let mut rights = Self::NONE;
+        if field == "-" {
+            return Ok(rights);
+        }
+        if field.is_empty() {
+            return Err(CastlingError::EmptyField);
+        }
+        for c in field.chars() {
+            if !matches!(c.to_ascii_lowercase(), 'k' | 'q' | 'a'..='h') {
+                return Err(CastlingError::InvalidCharacter(c));
+            }
+            let col = if c.is_ascii_uppercase() {
+                Col::White
+            } else {
+                Col::Black
+            };
+            let king = back_rank_king(board, col).ok_or(CastlingError::MissingKing(c))?;
+            let (side, file) = match c.to_ascii_lowercase() {
+                'k' => (
+                    CastlingSide::KingSide,
+                    outermost_rook(board, col, CastlingSide::KingSide),
+                ),
+                'q' => (
+                    CastlingSide::QueenSide,
+                    outermost_rook(board, col, CastlingSide::QueenSide),
+                ),
+                f @ 'a'..='h' => {
+                    let file = File::from_index(f as u8 - b'a').unwrap();
+                    let side = if file > king.file() {
+                        CastlingSide::KingSide
+                    } else {
+                        CastlingSide::QueenSide
+                    };
+                    let rook = Piece::new(col, PieceType::Rook);
+                    let on_back_rank = board.piece_at(back_rank_square(col, file)) == Some(rook);
+                    (side, on_back_rank.then_some(file))
+                }
+                _ => return Err(CastlingError::InvalidCharacter(c)),
+            };
+            let file = file.ok_or(CastlingError::MissingRook(c))?;
+            if file == king.file() {
+                return Err(CastlingError::MissingRook(c));
+            }
+            rights.set(col, side, file);
+        }
+        Ok(rights)
+    }
+    pub fn to_shredder(&self) -> String {
+        self.fen_field(|_, _, file| file_char(file))
+    }
+    // X-FEN uses KQkq where it is unambiguous and falls back to the rook
+    // file when an inner rook holds the right.
+    pub fn to_xfen(&self, board: &Board) -> String {
+        self.fen_field(|col, side, file| {
+            if outermost_rook(board, col, side) == Some(file) {
+                match side {
+                    CastlingSide::KingSide => 'k',
+                    CastlingSide::QueenSide => 'q',
+                }
+            } else {
+                file_char(file)
+            }
+        })
+    }
+    fn fen_field(&self, symbol: impl Fn(Col, CastlingSide, File) -> char) -> String {
+        let mut s = String::new();
+        for col in Col::all() {
+            for side in CastlingSide::all() {
+                if let Some(file) = self.rook(col, side) {
+                    let c = symbol(col, side, file);
+                    s.push(match col {
+                        Col::White => c.to_ascii_uppercase(),
+                        Col::Black => c,
+                    });
+                }
+            }
+        }
+        if s.is_empty() {
+            s.push('-');
+        }
+        s
+    }
+}
+
+impl fmt::Display for CastlingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyField => write!(f, "empty castling field"),
+            Self::InvalidCharacter(c) => write!(f, "invalid castling character '{c}'"),
+            Self::MissingKing(c) => write!(f, "castling right '{c}' without a king"),
+            Self::MissingRook(c) => write!(f, "castling right '{c}' without a rook"),
+        }
+    }
+}
+impl std::error::Error for CastlingError {}
+
+const fn file_char(file: File) -> char {
+    (b'a' + file as u8) as char
+}
+pub(crate) const fn back_rank_square(col: Col, file: File) -> Square {
+    match col {
+        Col::White => Square::from_rank_file(Rank::One, file),
+        Col::Black => Square::from_rank_file(Rank::Eight, file),
+    }
+}
+pub(crate) fn back_rank_king(board: &Board, col: Col) -> Option<Square> {
+    let back_rank = match col {
+        Col::White => BB_RANK_1,
+        Col::Black => BB_RANK_8,
+    };
+    let kings = board.by_piece(Piece::new(col, PieceType::King)) & back_rank;
+    if kings.one() { kings.lsb() } else { None }
+}
+fn outermost_rook(board: &Board, col: Col, side: CastlingSide) -> Option<File> {
+    let king = back_rank_king(board, col)?;
+    let mut rooks = board
+        .by_piece(Piece::new(col, PieceType::Rook))
+        .iter()
+        .filter(|sq| sq.rank() == king.rank())
+        .map(Square::file);
+    match side {
+        CastlingSide::KingSide => rooks.filter(|&f| f > king.file()).max(),
+        CastlingSide::QueenSide => rooks.find(|&f| f < king.file()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::*;
+    #[test]
+    fn index() {
+        assert_eq!(CastlingSide::KingSide.index(), 0);
+        assert_eq!(CastlingSide::QueenSide.index(), 1);
+    }
+    #[test]
+    fn set() {
+        let mut rights = CastlingRights::NONE;
+        assert!(rights.is_empty());
+        rights.set(Col::Black, CastlingSide::QueenSide, File::B);
+        assert!(rights.has(Col::Black, CastlingSide::QueenSide));
+        assert_eq!(
+            rights.rook(Col::Black, CastlingSide::QueenSide),
+            Some(File::B)
+        );
+        assert!(!rights.has(Col::White, CastlingSide::QueenSide));
+        rights.remove(Col::Black, CastlingSide::QueenSide);
+        assert!(rights.is_empty());
+        let mut rights = CastlingRights::CLASSICAL;
+        rights.remove_col(Col::White);
+        assert!(!rights.has(Col::White, CastlingSide::KingSide));
+        assert!(rights.has(Col::Black, CastlingSide::KingSide));
+    }
+    #[test]
+    fn from_board() {
+        assert_eq!(
+            CastlingRights::from_board(&Board::new()),
+            CastlingRights::CLASSICAL
+        );
+        let mut board = Board::new();
+        board.discard_piece_at(Square::A1);
+        board.discard_piece_at(Square::E8);
+        let rights = CastlingRights::from_board(&board);
+        assert_eq!(rights.to_shredder(), "H");
+        let rights = CastlingRights::from_board(&Board::horde());
+        assert_eq!(rights.to_xfen(&Board::horde()), "kq");
+    }
+    #[test]
+    fn from_fen_field() {
+        let board = Board::new();
+        assert_eq!(
+            CastlingRights::from_fen_field("KQkq", &board),
+            Ok(CastlingRights::CLASSICAL)
+        );
+        assert_eq!(
+            CastlingRights::from_fen_field("HAha", &board),
+            Ok(CastlingRights::CLASSICAL)
+        );
+        assert_eq!(
+            CastlingRights::from_fen_field("-", &board),
+            Ok(CastlingRights::NONE)
+        );
+        let rights = CastlingRights::from_fen_field("Kq", &board).unwrap();
+        assert_eq!(rights.to_shredder(), "Ha");
+        assert_eq!(
+            CastlingRights::from_fen_field("KX", &board),
+            Err(CastlingError::InvalidCharacter('X'))
+        );
+        assert_eq!(
+            CastlingRights::from_fen_field("B", &board),
+            Err(CastlingError::MissingRook('B'))
+        );
+        assert_eq!(
+            CastlingRights::from_fen_field("", &board),
+            Err(CastlingError::EmptyField)
+        );
+        let mut board = Board::new();
+        board.discard_piece_at(Square::E1);
+        assert_eq!(
+            CastlingRights::from_fen_field("K", &board),
+            Err(CastlingError::MissingKing('K'))
+        );
+        // a bad character is reported as such even without a king
+        assert_eq!(
+            CastlingRights::from_fen_field("1", &board),
+            Err(CastlingError::InvalidCharacter('1'))
+        );
+        assert_eq!(
+            CastlingRights::from_fen_field("kX", &board),
+            Err(CastlingError::InvalidCharacter('X'))
+        );
+        assert_eq!(
+            CastlingError::MissingKing('K').to_string(),
+            "castling right 'K' without a king"
+        );
+    }
+    #[test]
+    fn from_fen_field_inner_rook() {
+        // white king on c1 with rooks on a1, b1 and h1
+        let mut board = Board::empty();
+        board.set_piece_at(Square::A1, Piece::WR);
+        board.set_piece_at(Square::B1, Piece::WR);
+        board.set_piece_at(Square::C1, Piece::WK);
+        board.set_piece_at(Square::H1, Piece::WR);
+        board.set_piece_at(Square::C8, Piece::BK);
+        let rights = CastlingRights::from_fen_field("BH", &board).unwrap();
+        assert_eq!(
+            rights.rook(Col::White, CastlingSide::QueenSide),
+            Some(File::B)
+        );
+        assert_eq!(
+            rights.rook(Col::White, CastlingSide::KingSide),
+            Some(File::H)
+        );
+        assert_eq!(rights.to_xfen(&board), "KB");
+        let rights = CastlingRights::from_fen_field("Q", &board).unwrap();
+        assert_eq!(
+            rights.rook(Col::White, CastlingSide::QueenSide),
+            Some(File::A)
+        );
+    }
+    #[test]
+    fn to_shredder() {
+        assert_eq!(CastlingRights::CLASSICAL.to_shredder(), "HAha");
+        assert_eq!(CastlingRights::NONE.to_shredder(), "-");
+    }
+    #[test]
+    fn to_xfen() {
+        let board = Board::new();
+        assert_eq!(CastlingRights::CLASSICAL.to_xfen(&board), "KQkq");
+        assert_eq!(CastlingRights::NONE.to_xfen(&board), "-");
+    }
+}
@@ -6,6 +6,7 @@ impl Col {
     pub const fn new(v: bool) -> Self {
         if v { Self::Black } else { Self::White }
     }
+    #[must_use]
     pub const fn flip(self) -> Self {
         match self {
             Self::White => Self::Black,
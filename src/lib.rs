@@ -0,0 +1,56 @@
+//! Bitboard chess board representation and attack tables.
+//!
+//! Rook, bishop and queen attacks use fancy magic bitboards. With the `pext`
+//! feature on x86-64 targets that have BMI2 enabled (for example
+//! `RUSTFLAGS="-C target-cpu=native"`), `attacks::rook_attacks` and friends
+//! use PEXT-indexed tables instead; everywhere else the feature has no
+//! effect. The magic lookups stay available as const fns under their own
+//! names, such as `attacks::rook_attacks_magic`.
+//!
+//! ```
+//! use tuatara::{attacks, BitBoard, Board, Col, Piece, Square};
+//!
+//! let board = Board::new();
+//! assert_eq!(board.piece_at(Square::E1), Some(Piece::WK));
+//! assert_eq!(board.king_of(Col::Black), Some(Square::E8));
+//! let knight = attacks::knight_attacks(Square::G1);
+//! assert_eq!(knight & board.white(), BitBoard::from_square(Square::E2));
+//! assert_eq!(knight.popcount(), 3);
+//! ```
+
+pub mod attacks;
+mod bitboard;
+mod board;
+mod castling;
+mod col;
+mod consts;
+mod file;
+#[cfg(any(
+    test,
+    all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2")
+))]
+mod pext;
+pub mod pgn;
+mod piece;
+mod piecetype;
+pub mod polyglot;
+mod prng;
+mod rank;
+pub mod sliders;
+mod square;
+pub mod startpos;
+mod svg;
+pub mod timeman;
+mod validate;
+
+pub use crate::bitboard::SquareIter;
+pub use crate::consts::{
+    BB_DARK_SQUARES, BB_EMPTY, BB_FILE_A, BB_FILE_B, BB_FILE_C, BB_FILE_D, BB_FILE_E, BB_FILE_F,
+    BB_FILE_G, BB_FILE_H, BB_FULL, BB_LIGHT_SQUARES, BB_RANK_1, BB_RANK_2, BB_RANK_3, BB_RANK_4,
+    BB_RANK_5, BB_RANK_6, BB_RANK_7, BB_RANK_8, BISHOP_MAGICS, BitBoard, Board, BoardError, Book,
+    BookBuilder, BookError, BookOptions, CastlingError, CastlingRights, CastlingSide, Col, FILES,
+    File, GameResult, Limits, LimitsError, MAGIC_TABLE_SIZE, Magic, OptionError, ParsePieceError,
+    ParseSquareError, PgnError, PgnGame, Piece, PieceType, PolyglotEntry, PolyglotMove, Prng,
+    RANKS, ROOK_MAGICS, Rank, SQUARE_NAMES, Square, StartPos, SvgOptions, TimeControl, TimeManager,
+    Violation,
+};
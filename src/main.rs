@@ -1,17 +1,41 @@
-pub mod attacks;
-pub mod bitboard;
-pub mod board;
-pub mod col;
-pub mod consts;
-pub mod file;
-pub mod piece;
-pub mod piecetype;
-pub mod rank;
-pub mod square;
+use std::io::{self, BufRead, Write};
 
-use crate::consts::*;
+use tuatara::{Board, Col};
+
+const HELP: &str = "\
+commands:
+  show    print the board
+  flip    swap the board orientation
+  new     reset to the start position
+  help    show this message
+  quit    leave the console";
 
 fn main() {
-    let bd = Board::new();
-    println!("Initial board state:\n{:#?}", bd);
+    let mut bd = Board::new();
+    let mut perspective = Col::White;
+    print!("{}", bd.render(perspective));
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+        let Some(Ok(line)) = lines.next() else {
+            break;
+        };
+        match line.trim() {
+            "" => {}
+            "quit" | "exit" => break,
+            "help" => println!("{HELP}"),
+            "show" => print!("{}", bd.render(perspective)),
+            "flip" => {
+                perspective = !perspective;
+                print!("{}", bd.render(perspective));
+            }
+            "new" => {
+                bd = Board::new();
+                print!("{}", bd.render(perspective));
+            }
+            cmd => println!("unknown command: {cmd} (try help)"),
+        }
+    }
 }
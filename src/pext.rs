@@ -0,0 +1,166 @@
+// PEXT-indexed slider attack tables.
+//
+// With BMI2 the index into the table is simply the occupied squares of the
+// relevant mask packed into the low bits, so no magic factors are needed.
+// The tables are built at compile time with a software PEXT; lookups use
+// the hardware instruction when the target has it.
+use crate::attacks::sliding_attacks;
+use crate::consts::*;
+
+const BISHOP_TABLE_SIZE: usize = 5248;
+const ROOK_TABLE_SIZE: usize = 102400;
+const TABLE_SIZE: usize = BISHOP_TABLE_SIZE + ROOK_TABLE_SIZE;
+
+// Parallel bit extract: the bits of `x` selected by `mask`, packed into
+// the low bits of the result.
+#[cfg(any(test, not(all(target_arch = "x86_64", target_feature = "bmi2"))))]
+const fn soft_pext(x: u64, mask: u64) -> u64 {
+    let mut result = 0;
+    let mut mask = mask;
+    let mut bit = 1;
+    while mask != 0 {
+        if x & mask & mask.wrapping_neg() != 0 {
+            result |= bit;
+        }
+        mask &= mask - 1;
+        bit <<= 1;
+    }
+    result
+}
+#[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+#[inline(always)]
+fn pext(x: u64, mask: u64) -> u64 {
+    // SAFETY: this is only compiled when the target has BMI2 enabled.
+    unsafe { core::arch::x86_64::_pext_u64(x, mask) }
+}
+#[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+#[inline(always)]
+fn pext(x: u64, mask: u64) -> u64 {
+    soft_pext(x, mask)
+}
+
+const fn bootstrap_offsets(magics: &[Magic; 64], start: usize) -> [usize; 64] {
+    let mut offsets = [0; 64];
+    let mut offset = start;
+    let mut square = 0;
+    while square < 64 {
+        offsets[square] = offset;
+        offset += 1 << magics[square].mask.count_ones();
+        square += 1;
+    }
+    offsets
+}
+static BISHOP_OFFSETS: [usize; 64] = bootstrap_offsets(&BISHOP_MAGICS, 0);
+static ROOK_OFFSETS: [usize; 64] = bootstrap_offsets(&ROOK_MAGICS, BISHOP_TABLE_SIZE);
+
+const fn bootstrap_pext(
+    table: &mut [u64; TABLE_SIZE],
+    magics: &[Magic; 64],
+    start: usize,
+    deltas: &[i32],
+) -> usize {
+    let mut offset = start;
+    let mut square = 0;
+    while square < 64 {
+        // The carry-rippler visits the subsets of the mask in the same
+        // order as their PEXT indices, so the index is just a counter.
+        let mask = magics[square].mask;
+        let mut subset = 0;
+        loop {
+            table[offset] = sliding_attacks(square as i32, subset, deltas);
+            offset += 1;
+            subset = subset.wrapping_sub(mask) & mask;
+            if subset == 0 {
+                break;
+            }
+        }
+        square += 1;
+    }
+    offset
+}
+const fn bootstrap_tables() -> [u64; TABLE_SIZE] {
+    let mut table = [0; TABLE_SIZE];
+    let end = bootstrap_pext(&mut table, &BISHOP_MAGICS, 0, &BISHOP_DELTAS);
+    assert!(end == BISHOP_TABLE_SIZE);
+    let end = bootstrap_pext(&mut table, &ROOK_MAGICS, end, &ROOK_DELTAS);
+    assert!(end == TABLE_SIZE);
+    table
+}
+static ATTACKS: [u64; TABLE_SIZE] = bootstrap_tables();
+
+pub fn rook_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+    let idx = pext(occupied.0, ROOK_MAGICS[sq.index()].mask) as usize;
+    BitBoard(ATTACKS[ROOK_OFFSETS[sq.index()] + idx])
+}
+pub fn bishop_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+    let idx = pext(occupied.0, BISHOP_MAGICS[sq.index()].mask) as usize;
+    BitBoard(ATTACKS[BISHOP_OFFSETS[sq.index()] + idx])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::attacks;
+    use crate::consts::*;
+    use crate::pext;
+
+    #[test]
+    fn soft_pext() {
+        assert_eq!(pext::soft_pext(0, BB_FULL.0), 0);
+        assert_eq!(pext::soft_pext(0b1010, 0b1110), 0b101);
+        assert_eq!(pext::soft_pext(BB_RANK_2.0, BB_FILE_A.0), 0b10);
+        assert_eq!(pext::soft_pext(!0, BB_FILE_D.0 | BB_RANK_5.0), 0x7fff);
+    }
+    #[test]
+    fn pext_matches_soft_pext() {
+        let mask = ROOK_MAGICS[Square::D4.index()].mask;
+        for x in [0, 1, 0x1234_5678_9abc_def0, !0] {
+            assert_eq!(pext::pext(x, mask), pext::soft_pext(x, mask));
+        }
+    }
+    // every square with every subset of its relevant occupancy mask
+    #[test]
+    fn matches_magics() {
+        for sq in Square::all() {
+            let mask = ROOK_MAGICS[sq.index()].mask;
+            let mut subset = 0u64;
+            loop {
+                let occupied = BitBoard(subset);
+                assert_eq!(
+                    pext::rook_attacks(sq, occupied),
+                    attacks::rook_attacks_magic(sq, occupied)
+                );
+                subset = subset.wrapping_sub(mask) & mask;
+                if subset == 0 {
+                    break;
+                }
+            }
+            let mask = BISHOP_MAGICS[sq.index()].mask;
+            let mut subset = 0u64;
+            loop {
+                let occupied = BitBoard(subset);
+                assert_eq!(
+                    pext::bishop_attacks(sq, occupied),
+                    attacks::bishop_attacks_magic(sq, occupied)
+                );
+                subset = subset.wrapping_sub(mask) & mask;
+                if subset == 0 {
+                    break;
+                }
+            }
+        }
+    }
+    #[test]
+    fn ignores_irrelevant_squares() {
+        let occupied = BB_RANK_1 | BB_FILE_H | Square::D4.as_bb();
+        for sq in Square::all() {
+            assert_eq!(
+                pext::rook_attacks(sq, occupied),
+                attacks::rook_attacks_magic(sq, occupied)
+            );
+            assert_eq!(
+                pext::bishop_attacks(sq, occupied),
+                attacks::bishop_attacks_magic(sq, occupied)
+            );
+        }
+    }
+}
@@ -0,0 +1,429 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::attacks;
+use crate::castling::back_rank_square;
+use crate::consts::*;
+use crate::polyglot;
+
+// Splits PGN text into games. Comments, variations and annotation glyphs
+// are skipped; the parser never fails, so a damaged game only shows up
+// when its moves are replayed.
+pub fn parse(text: &str) -> Vec<PgnGame> {
+    let mut games = Vec::new();
+    let mut game = PgnGame::default();
+    let mut finished = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '[' => {
+                // a tag after the movetext starts the next game
+                if finished || !game.moves.is_empty() {
+                    games.push(std::mem::take(&mut game));
+                    finished = false;
+                }
+                let tag: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                if let Some((name, value)) = tag.split_once(char::is_whitespace) {
+                    let value = value.trim().trim_matches('"').replace("\\\"", "\"");
+                    game.tags.push((name.to_string(), value));
+                }
+            }
+            '{' => chars.by_ref().take_while(|&c| c != '}').for_each(drop),
+            ';' => chars.by_ref().take_while(|&c| c != '\n').for_each(drop),
+            '(' => {
+                let mut depth = 1;
+                while depth > 0 {
+                    match chars.next() {
+                        Some('(') => depth += 1,
+                        Some(')') => depth -= 1,
+                        Some('{') => chars.by_ref().take_while(|&c| c != '}').for_each(drop),
+                        Some(_) => {}
+                        None => break,
+                    }
+                }
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut token = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "[]{}();".contains(c) {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                let result = match token.as_str() {
+                    "1-0" => Some(Some(GameResult::WhiteWins)),
+                    "0-1" => Some(Some(GameResult::BlackWins)),
+                    "1/2-1/2" => Some(Some(GameResult::Draw)),
+                    "*" => Some(None),
+                    _ => None,
+                };
+                if finished {
+                    games.push(std::mem::take(&mut game));
+                    finished = false;
+                }
+                if let Some(result) = result {
+                    game.result = result;
+                    finished = true;
+                    continue;
+                }
+                // move numbers may be glued to the move, as in "1.e4"
+                let san = token.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+                if !san.is_empty() && !san.starts_with('$') {
+                    game.moves.push(san.to_string());
+                }
+            }
+        }
+    }
+    if finished || !game.moves.is_empty() || !game.tags.is_empty() {
+        games.push(game);
+    }
+    for game in &mut games {
+        if game.result.is_none() {
+            game.result = match game.tag("Result") {
+                Some("1-0") => Some(GameResult::WhiteWins),
+                Some("0-1") => Some(GameResult::BlackWins),
+                Some("1/2-1/2") => Some(GameResult::Draw),
+                _ => None,
+            };
+        }
+    }
+    games
+}
+
+impl PgnGame {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+    // The rating of the player of the given side, from the WhiteElo and
+    // BlackElo tags.
+    pub fn elo(&self, col: Col) -> Option<u32> {
+        let tag = match col {
+            Col::White => "WhiteElo",
+            Col::Black => "BlackElo",
+        };
+        self.tag(tag)?.parse().ok()
+    }
+    // Replays the first `max_plies` moves from the start position, giving
+    // the polyglot key of the position before each move, the move in
+    // polyglot form and the side that played it.
+    pub fn replay(&self, max_plies: usize) -> Result<Vec<(u64, PolyglotMove, Col)>, PgnError> {
+        if self.tag("FEN").is_some() {
+            return Err(PgnError::CustomStart);
+        }
+        let mut position = Position::new();
+        let mut played = Vec::new();
+        for (ply, san) in self.moves.iter().take(max_plies).enumerate() {
+            let mv = position
+                .parse_san(san)
+                .ok_or_else(|| PgnError::InvalidMove {
+                    ply: ply + 1,
+                    san: san.clone(),
+                })?;
+            played.push((position.key(), mv, position.turn));
+            position.play(mv);
+        }
+        Ok(played)
+    }
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMove { ply, san } => {
+                write!(f, "invalid or illegal move {san:?} at ply {ply}")
+            }
+            Self::CustomStart => write!(f, "games from a custom start position are not supported"),
+        }
+    }
+}
+impl std::error::Error for PgnError {}
+
+// Just enough state to replay standard chess games: the board does not
+// track the side to move, castling rights or the en passant square.
+#[derive(Clone)]
+struct Position {
+    board: Board,
+    turn: Col,
+    castling: CastlingRights,
+    ep_square: Option<Square>,
+}
+impl Position {
+    fn new() -> Self {
+        Position {
+            board: Board::new(),
+            turn: Col::White,
+            castling: CastlingRights::CLASSICAL,
+            ep_square: None,
+        }
+    }
+    fn key(&self) -> u64 {
+        polyglot::key(&self.board, self.turn, &self.castling, self.ep_square)
+    }
+    // Castling comes back as the king taking its own rook, as polyglot
+    // stores it.
+    fn parse_san(&self, san: &str) -> Option<PolyglotMove> {
+        let san = san.trim_end_matches(['+', '#', '!', '?']);
+        let side = match san {
+            "O-O" | "0-0" => Some(CastlingSide::KingSide),
+            "O-O-O" | "0-0-0" => Some(CastlingSide::QueenSide),
+            _ => None,
+        };
+        if let Some(side) = side {
+            let file = self.castling.rook(self.turn, side)?;
+            return Some(PolyglotMove {
+                from: self.board.king_of(self.turn)?,
+                to: back_rank_square(self.turn, file),
+                promotion: None,
+            });
+        }
+        let (san, promotion) = match san.split_once('=') {
+            Some((san, promo)) => (san, Some(promo)),
+            None if san.ends_with(|c: char| "NBRQ".contains(c)) => san
+                .split_at_checked(san.len() - 1)
+                .map(|(san, p)| (san, Some(p)))?,
+            None => (san, None),
+        };
+        let promotion = match promotion {
+            Some(p) => match PieceType::from_symbol(*p.as_bytes().first()?)? {
+                pt @ (PieceType::Knight
+                | PieceType::Bishop
+                | PieceType::Rook
+                | PieceType::Queen) => Some(pt),
+                _ => return None,
+            },
+            None => None,
+        };
+        let (piecetype, san) = match san.as_bytes().first()? {
+            c if c.is_ascii_uppercase() => (PieceType::from_symbol(*c)?, &san[1..]),
+            _ => (PieceType::Pawn, san),
+        };
+        let capture = san.contains('x');
+        let san = san.replace('x', "");
+        let (hint, to) = san.split_at_checked(san.len().checked_sub(2)?)?;
+        let to = Square::from_str(to).ok()?;
+        let last_rank = to.relative_to(self.turn).rank() == Rank::Eight;
+        if self.board.by_col(self.turn).contains_square(to)
+            || promotion.is_some() != (piecetype == PieceType::Pawn && last_rank)
+        {
+            return None;
+        }
+        let piece = Piece::new(self.turn, piecetype);
+        let candidates = match piecetype {
+            PieceType::Pawn if capture => {
+                let target =
+                    self.board.by_col(!self.turn).contains_square(to) || self.ep_square == Some(to);
+                if !target {
+                    return None;
+                }
+                attacks::pawn_attacks(!self.turn, to) & self.board.by_piece(piece)
+            }
+            PieceType::Pawn => {
+                if self.board.occupied().contains_square(to) {
+                    return None;
+                }
+                let one = to.pawn_push(!self.turn)?;
+                match self.board.piece_at(one) {
+                    Some(p) if p == piece => one.as_bb(),
+                    None if to.relative_to(self.turn).rank() == Rank::Four => {
+                        one.pawn_push(!self.turn)?.as_bb() & self.board.by_piece(piece)
+                    }
+                    _ => BB_EMPTY,
+                }
+            }
+            _ => attacks::attacks(to, piece, self.board.occupied()) & self.board.by_piece(piece),
+        };
+        let mut moves = candidates
+            .into_iter()
+            .filter(|from| {
+                hint.chars().all(|c| match c {
+                    'a'..='h' => from.file() as u8 == c as u8 - b'a',
+                    '1'..='8' => from.rank() as u8 == c as u8 - b'1',
+                    _ => false,
+                })
+            })
+            .map(|from| PolyglotMove {
+                from,
+                to,
+                promotion,
+            })
+            .filter(|&mv| self.is_legal(mv));
+        let mv = moves.next()?;
+        moves.next().is_none().then_some(mv)
+    }
+    // whether the move leaves the mover's king safe
+    fn is_legal(&self, mv: PolyglotMove) -> bool {
+        let mut next = self.clone();
+        next.play(mv);
+        next.board.king_of(self.turn).is_none_or(|king| {
+            next.board
+                .atacks_to(king, !self.turn, next.board.occupied())
+                .is_empty()
+        })
+    }
+    fn play(&mut self, mv: PolyglotMove) {
+        let us = self.turn;
+        let Some(piece) = self.board.remove_piece_at(mv.from) else {
+            return;
+        };
+        let rook = Piece::new(us, PieceType::Rook);
+        if piece.piecetype() == PieceType::King && self.board.piece_at(mv.to) == Some(rook) {
+            self.board.discard_piece_at(mv.to);
+            let (king_file, rook_file) = if mv.to.file() > mv.from.file() {
+                (File::G, File::F)
+            } else {
+                (File::C, File::D)
+            };
+            self.board
+                .set_piece_at(back_rank_square(us, king_file), piece);
+            self.board
+                .set_piece_at(back_rank_square(us, rook_file), rook);
+        } else {
+            if piece.piecetype() == PieceType::Pawn
+                && Some(mv.to) == self.ep_square
+                && self.board.piece_at(mv.to).is_none()
+                && let Some(captured) = mv.to.pawn_push(!us)
+            {
+                self.board.discard_piece_at(captured);
+            }
+            let placed = mv.promotion.map_or(piece, |pt| Piece::new(us, pt));
+            self.board.set_piece_at(mv.to, placed);
+        }
+        if piece.piecetype() == PieceType::King {
+            self.castling.remove_col(us);
+        }
+        for col in Col::all() {
+            for side in CastlingSide::all() {
+                if let Some(file) = self.castling.rook(col, side) {
+                    let home = back_rank_square(col, file);
+                    if mv.from == home || mv.to == home {
+                        self.castling.remove(col, side);
+                    }
+                }
+            }
+        }
+        self.ep_square =
+            if piece.piecetype() == PieceType::Pawn && mv.from.rank().abs_diff(mv.to.rank()) == 2 {
+                mv.from.pawn_push(us)
+            } else {
+                None
+            };
+        self.turn = !us;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::*;
+    use crate::pgn;
+
+    const GAMES: &str = r#"[Event "Fixture"]
+[White "A"]
+[Black "B"]
+[WhiteElo "2500"]
+[BlackElo "2100"]
+[Result "1-0"]
+
+1. e4 {best by test} d5 2. e5 f5 3. Ke2 (3. exf6 $1 Nxf6) 3... Kf7 ; a comment
+4. Nf3 Nc6 5. Nc3 Nb4 6. Kd1 1-0
+
+[Event "Fixture"]
+[Result "1/2-1/2"]
+
+1.a4 b5 2.h4 b4 3.c4 bxc3 4.Ra3 c2 5.Nf3 cxb1=Q 6.Ne5 Qxc1 7.Rah3 Qxd1+ 8.Kxd1 1/2-1/2
+"#;
+
+    #[test]
+    fn parse() {
+        let games = pgn::parse(GAMES);
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].tag("White"), Some("A"));
+        assert_eq!(games[0].elo(Col::White), Some(2500));
+        assert_eq!(games[0].elo(Col::Black), Some(2100));
+        assert_eq!(games[1].elo(Col::White), None);
+        assert_eq!(games[0].result, Some(GameResult::WhiteWins));
+        assert_eq!(games[1].result, Some(GameResult::Draw));
+        assert_eq!(
+            games[0].moves,
+            [
+                "e4", "d5", "e5", "f5", "Ke2", "Kf7", "Nf3", "Nc6", "Nc3", "Nb4", "Kd1"
+            ]
+        );
+        assert_eq!(games[1].moves.len(), 15);
+        let games = pgn::parse("[Result \"*\"]\n1. d4 *\n1. c4");
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].result, None);
+        assert_eq!(games[1].moves, ["c4"]);
+    }
+    // the example positions of the polyglot book format specification
+    #[test]
+    fn replay() {
+        let games = pgn::parse(GAMES);
+        let keys: Vec<u64> = games[0].replay(7).unwrap().iter().map(|e| e.0).collect();
+        assert_eq!(
+            keys,
+            [
+                0x463b_9618_1691_fc9c,
+                0x823c_9b50_fd11_4196,
+                0x0756_b944_61c5_0fb0,
+                0x662f_afb9_65db_29d4,
+                0x22a4_8b5a_8e47_ff78,
+                0x652a_607c_a3f2_42c1,
+                0x00fd_d303_c946_bdd9,
+            ]
+        );
+        let played = games[1].replay(usize::MAX).unwrap();
+        assert_eq!(played[5].0, 0x3c81_23ea_7b06_7637);
+        assert_eq!(played[7].0, 0x5c3f_9b82_9b27_9560);
+        let (_, mv, col) = played[9];
+        assert_eq!(mv.to_string(), "c2b1q");
+        assert_eq!(col, Col::Black);
+        assert_eq!(played.len(), 15);
+    }
+    #[test]
+    fn san() {
+        let game = &pgn::parse(
+            "1. e4 e5 2. Nf3 Nf6 3. Bc4 Bc5 4. O-O d6 5. d3 Bg4 6. Nbd2 Nbd7 \
+             7. c3 Qe7 8. Re1 O-O-O *",
+        )[0];
+        let played = game.replay(usize::MAX).unwrap();
+        let moves: Vec<String> = played.iter().map(|(_, mv, _)| mv.to_string()).collect();
+        // castling is stored as the king taking its rook
+        assert_eq!(moves[6], "e1h1");
+        assert_eq!(moves[11], "b8d7");
+        assert_eq!(moves[14], "f1e1");
+        assert_eq!(moves[15], "e8a8");
+        // the knight on c3 is pinned, so only the one on g1 can go to e2
+        let game = &pgn::parse("1. d4 e5 2. Nc3 Bb4 3. e3 a6 4. Ne2 *")[0];
+        let (_, mv, _) = game.replay(usize::MAX).unwrap()[6];
+        assert_eq!(mv.to_string(), "g1e2");
+        let err = pgn::parse("1. e4 e5 2. Ke3")[0]
+            .replay(usize::MAX)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            PgnError::InvalidMove {
+                ply: 3,
+                san: "Ke3".to_string()
+            }
+        );
+        assert_eq!(err.to_string(), "invalid or illegal move \"Ke3\" at ply 3");
+        // both knights can reach d2 unless told apart
+        let game = &pgn::parse("1. Nf3 d5 2. d4 e6 3. Nbd2 c5 4. Nb3 Nc6 5. Nbd2 *")[0];
+        assert!(game.replay(usize::MAX).is_ok());
+        let game = &pgn::parse("1. Nf3 d5 2. d4 e6 3. Nbd2 c5 4. Nb3 Nc6 5. Nd2 *")[0];
+        assert_eq!(
+            game.replay(usize::MAX),
+            Err(PgnError::InvalidMove {
+                ply: 9,
+                san: "Nd2".to_string()
+            })
+        );
+        let game = &pgn::parse("[FEN \"8/8/8/8/8/8/8/K6k w - - 0 1\"]\n1. Kb1 *")[0];
+        assert_eq!(game.replay(usize::MAX), Err(PgnError::CustomStart));
+    }
+}
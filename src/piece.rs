@@ -1,6 +1,7 @@
 use crate::consts::*;
 use std::fmt::Display;
 use std::ops::{Index, IndexMut};
+use std::str::FromStr;
 
 impl Piece {
     pub const fn new(colour: Col, piece_type: PieceType) -> Self {
@@ -50,6 +51,40 @@ impl Piece {
             Self::BK => 'k',
         }
     }
+    pub const fn glyph(self) -> char {
+        match self {
+            Self::WP => '♙',
+            Self::WN => '♘',
+            Self::WB => '♗',
+            Self::WR => '♖',
+            Self::WQ => '♕',
+            Self::WK => '♔',
+            Self::BP => '♟',
+            Self::BN => '♞',
+            Self::BB => '♝',
+            Self::BR => '♜',
+            Self::BQ => '♛',
+            Self::BK => '♚',
+        }
+    }
+    // The inverse of char: a FEN piece letter.
+    pub const fn from_char(c: char) -> Option<Self> {
+        match c {
+            'P' => Some(Self::WP),
+            'N' => Some(Self::WN),
+            'B' => Some(Self::WB),
+            'R' => Some(Self::WR),
+            'Q' => Some(Self::WQ),
+            'K' => Some(Self::WK),
+            'p' => Some(Self::BP),
+            'n' => Some(Self::BN),
+            'b' => Some(Self::BB),
+            'r' => Some(Self::BR),
+            'q' => Some(Self::BQ),
+            'k' => Some(Self::BK),
+            _ => None,
+        }
+    }
     pub fn byte_char(self) -> u8 {
         b"PNBRQKpnbrqk"[self]
     }
@@ -66,6 +101,30 @@ impl Display for Piece {
         write!(f, "{}", self.char())
     }
 }
+impl FromStr for Piece {
+    type Err = ParsePieceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Self::from_char(c),
+            _ => None,
+        }
+        .ok_or_else(|| ParsePieceError(s.to_string()))
+    }
+}
+impl ParsePieceError {
+    // the text that failed to parse
+    pub fn input(&self) -> &str {
+        &self.0
+    }
+}
+impl Display for ParsePieceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid piece {:?}", self.0)
+    }
+}
+impl std::error::Error for ParsePieceError {}
 impl<T> Index<Piece> for [T; 12] {
     type Output = T;
 
@@ -212,6 +271,34 @@ mod tests {
         assert_eq!(Piece::BK.char(), 'k');
     }
     #[test]
+    fn from_char() {
+        for piece in Piece::all() {
+            assert_eq!(Piece::from_char(piece.char()), Some(piece));
+            assert_eq!(piece.to_string().parse(), Ok(piece));
+        }
+        assert_eq!(Piece::from_char('x'), None);
+        let err = "Kq".parse::<Piece>().unwrap_err();
+        assert_eq!(err, ParsePieceError("Kq".to_string()));
+        assert_eq!(err.input(), "Kq");
+        assert_eq!(err.to_string(), "invalid piece \"Kq\"");
+        assert!("".parse::<Piece>().is_err());
+    }
+    #[test]
+    fn glyph() {
+        assert_eq!(Piece::WP.glyph(), '♙');
+        assert_eq!(Piece::BP.glyph(), '♟');
+        assert_eq!(Piece::WN.glyph(), '♘');
+        assert_eq!(Piece::BN.glyph(), '♞');
+        assert_eq!(Piece::WB.glyph(), '♗');
+        assert_eq!(Piece::BB.glyph(), '♝');
+        assert_eq!(Piece::WR.glyph(), '♖');
+        assert_eq!(Piece::BR.glyph(), '♜');
+        assert_eq!(Piece::WQ.glyph(), '♕');
+        assert_eq!(Piece::BQ.glyph(), '♛');
+        assert_eq!(Piece::WK.glyph(), '♔');
+        assert_eq!(Piece::BK.glyph(), '♚');
+    }
+    #[test]
     fn byte_char() {
         assert_eq!(Piece::WP.byte_char(), b'P');
         assert_eq!(Piece::BP.byte_char(), b'p');
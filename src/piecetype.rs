@@ -11,6 +11,8 @@ impl PieceType {
             None
         }
     }
+    /// # Safety
+    /// `v` must be less than 6.
     pub const unsafe fn from_index_unchecked(v: u8) -> Self {
         unsafe { std::mem::transmute(v) }
     }
@@ -0,0 +1,61 @@
+use crate::consts::*;
+
+// xorshift64* generator: small, fast and reproducible from a seed,
+// which is all that book move selection and setup generation need.
+impl Prng {
+    pub const fn new(seed: u64) -> Self {
+        // the all-zero state is a fixed point of xorshift, so avoid it
+        if seed == 0 {
+            Prng(0x9e37_79b9_7f4a_7c15)
+        } else {
+            Prng(seed)
+        }
+    }
+    pub const fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+    pub const fn sparse_u64(&mut self) -> u64 {
+        self.next_u64() & self.next_u64() & self.next_u64()
+    }
+    pub const fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0);
+        self.next_u64() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::*;
+    #[test]
+    fn new() {
+        assert_eq!(Prng::new(7), Prng(7));
+        assert_ne!(Prng::new(0), Prng(0));
+    }
+    #[test]
+    fn next_u64() {
+        let mut a = Prng::new(42);
+        let mut b = Prng::new(42);
+        let first = a.next_u64();
+        assert_eq!(first, b.next_u64());
+        assert_ne!(first, a.next_u64());
+        let mut z = Prng::new(0);
+        assert_ne!(z.next_u64(), 0);
+    }
+    #[test]
+    fn sparse_u64() {
+        let mut rng = Prng::new(1);
+        let total: u32 = (0..64).map(|_| rng.sparse_u64().count_ones()).sum();
+        assert!(total < 64 * 16);
+    }
+    #[test]
+    fn below() {
+        let mut rng = Prng::new(3);
+        for _ in 0..1000 {
+            assert!(rng.below(10) < 10);
+        }
+        assert_eq!(rng.below(1), 0);
+    }
+}
@@ -0,0 +1,284 @@
+// Interchangeable implementations of the slider attack functions.
+//
+// `attacks::rook_attacks` and `attacks::bishop_attacks` pick PEXT or magics
+// when the crate is compiled. Code that wants to compare backends, or run
+// on a platform where another one wins, can be written against the
+// `Sliders` trait instead.
+use crate::attacks::{bishop_attacks_magic, rook_attacks_magic, sliding_attacks};
+use crate::consts::*;
+
+pub trait Sliders {
+    const NAME: &'static str;
+    fn rook_attacks(sq: Square, occupied: BitBoard) -> BitBoard;
+    fn bishop_attacks(sq: Square, occupied: BitBoard) -> BitBoard;
+    fn queen_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        Self::rook_attacks(sq, occupied) | Self::bishop_attacks(sq, occupied)
+    }
+}
+
+// Fancy magic bitboards: the default tables.
+pub struct FancyMagic;
+impl Sliders for FancyMagic {
+    const NAME: &'static str = "magic";
+    fn rook_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        rook_attacks_magic(sq, occupied)
+    }
+    fn bishop_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        bishop_attacks_magic(sq, occupied)
+    }
+}
+
+// PEXT-indexed tables with the `pext` feature on BMI2. Elsewhere PEXT
+// would have to be emulated in software, so this forwards to FancyMagic.
+pub struct Pext;
+#[cfg(all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2"))]
+impl Sliders for Pext {
+    const NAME: &'static str = "pext";
+    fn rook_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        crate::pext::rook_attacks(sq, occupied)
+    }
+    fn bishop_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        crate::pext::bishop_attacks(sq, occupied)
+    }
+}
+#[cfg(not(all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2")))]
+impl Sliders for Pext {
+    const NAME: &'static str = "pext (magic)";
+    fn rook_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        FancyMagic::rook_attacks(sq, occupied)
+    }
+    fn bishop_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        FancyMagic::bishop_attacks(sq, occupied)
+    }
+}
+
+const fn bootstrap_lines(deltas: &[i32]) -> [u64; 64] {
+    let mut table = [0; 64];
+    let mut sq = 0;
+    while sq < 64 {
+        table[sq] = sliding_attacks(sq as i32, 0, deltas);
+        sq += 1;
+    }
+    table
+}
+// Lines through each square, excluding the square itself.
+static FILE_LINES: [u64; 64] = bootstrap_lines(&[8, -8]);
+static RANK_LINES: [u64; 64] = bootstrap_lines(&[1, -1]);
+static DIAGONAL_LINES: [u64; 64] = bootstrap_lines(&[9, -9]);
+static ANTI_DIAGONAL_LINES: [u64; 64] = bootstrap_lines(&[7, -7]);
+
+// Hyperbola quintessence: o ^ (o - 2r) for the attacks towards the high
+// bits, and the same on the bit-reversed board for the other direction.
+pub struct Hyperbola;
+impl Hyperbola {
+    const fn line_attacks(sq: Square, occupied: u64, line: u64) -> u64 {
+        let slider = 1u64 << sq.index();
+        let forward = occupied & line;
+        let reverse = forward.reverse_bits();
+        let forward = forward.wrapping_sub(slider.wrapping_mul(2));
+        let reverse = reverse.wrapping_sub(slider.reverse_bits().wrapping_mul(2));
+        (forward ^ reverse.reverse_bits()) & line
+    }
+}
+impl Sliders for Hyperbola {
+    const NAME: &'static str = "hyperbola";
+    fn rook_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        let i = sq.index();
+        BitBoard(
+            Self::line_attacks(sq, occupied.0, FILE_LINES[i])
+                | Self::line_attacks(sq, occupied.0, RANK_LINES[i]),
+        )
+    }
+    fn bishop_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        let i = sq.index();
+        BitBoard(
+            Self::line_attacks(sq, occupied.0, DIAGONAL_LINES[i])
+                | Self::line_attacks(sq, occupied.0, ANTI_DIAGONAL_LINES[i]),
+        )
+    }
+}
+
+// Kogge-Stone occluded fills, with no tables at all.
+pub struct KoggeStone;
+impl Sliders for KoggeStone {
+    const NAME: &'static str = "kogge-stone";
+    fn rook_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        let bb = sq.as_bb();
+        let empty = !occupied;
+        bb.north_occluded(empty).north_one()
+            | bb.south_occluded(empty).south_one()
+            | bb.east_occluded(empty).east_one()
+            | bb.west_occluded(empty).west_one()
+    }
+    fn bishop_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        let bb = sq.as_bb();
+        let empty = !occupied;
+        bb.north_east_occluded(empty).north_east_one()
+            | bb.north_west_occluded(empty).north_west_one()
+            | bb.south_east_occluded(empty).south_east_one()
+            | bb.south_west_occluded(empty).south_west_one()
+    }
+}
+
+// Rays in each direction, indexed [delta][square] in the order of
+// ROOK_DELTAS and BISHOP_DELTAS: two positive deltas, then two negative.
+static ROOK_RAYS: [[u64; 64]; 4] = [
+    bootstrap_lines(&[ROOK_DELTAS[0]]),
+    bootstrap_lines(&[ROOK_DELTAS[1]]),
+    bootstrap_lines(&[ROOK_DELTAS[2]]),
+    bootstrap_lines(&[ROOK_DELTAS[3]]),
+];
+static BISHOP_RAYS: [[u64; 64]; 4] = [
+    bootstrap_lines(&[BISHOP_DELTAS[0]]),
+    bootstrap_lines(&[BISHOP_DELTAS[1]]),
+    bootstrap_lines(&[BISHOP_DELTAS[2]]),
+    bootstrap_lines(&[BISHOP_DELTAS[3]]),
+];
+
+// Classical ray lookup: each ray is cut at its first blocker, which is
+// the lowest set bit for positive directions and the highest otherwise.
+pub struct Classical;
+impl Classical {
+    fn ray_attacks(rays: &[[u64; 64]; 4], sq: Square, occupied: u64) -> u64 {
+        let mut attacks = 0;
+        for (dir, ray) in rays.iter().enumerate() {
+            let ray = ray[sq.index()];
+            let blockers = ray & occupied;
+            attacks |= if blockers == 0 {
+                ray
+            } else if dir < 2 {
+                ray ^ rays[dir][blockers.trailing_zeros() as usize]
+            } else {
+                ray ^ rays[dir][63 - blockers.leading_zeros() as usize]
+            };
+        }
+        attacks
+    }
+}
+impl Sliders for Classical {
+    const NAME: &'static str = "classical";
+    fn rook_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        BitBoard(Self::ray_attacks(&ROOK_RAYS, sq, occupied.0))
+    }
+    fn bishop_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        BitBoard(Self::ray_attacks(&BISHOP_RAYS, sq, occupied.0))
+    }
+}
+
+// The square-by-square loop the tables are bootstrapped from.
+pub struct Loop;
+impl Sliders for Loop {
+    const NAME: &'static str = "loop";
+    fn rook_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        BitBoard(sliding_attacks(sq.index() as i32, occupied.0, &ROOK_DELTAS))
+    }
+    fn bishop_attacks(sq: Square, occupied: BitBoard) -> BitBoard {
+        BitBoard(sliding_attacks(
+            sq.index() as i32,
+            occupied.0,
+            &BISHOP_DELTAS,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::*;
+    use crate::sliders::*;
+
+    type Attacks = fn(Square, BitBoard) -> BitBoard;
+
+    // every square with every subset of its relevant occupancy mask, plus
+    // the same subsets with all irrelevant squares filled
+    fn matches(name: &str, magics: &[Magic; 64], attacks: Attacks, reference: Attacks) {
+        for sq in Square::all() {
+            let mask = magics[sq.index()].mask;
+            let mut subset = 0u64;
+            loop {
+                for occupied in [BitBoard(subset), BitBoard(subset | !mask)] {
+                    assert_eq!(
+                        attacks(sq, occupied),
+                        reference(sq, occupied),
+                        "{name} on {sq} with\n{occupied}"
+                    );
+                }
+                subset = subset.wrapping_sub(mask) & mask;
+                if subset == 0 {
+                    break;
+                }
+            }
+        }
+    }
+    fn matches_magic<S: Sliders>() {
+        matches(
+            S::NAME,
+            &ROOK_MAGICS,
+            S::rook_attacks,
+            FancyMagic::rook_attacks,
+        );
+        matches(
+            S::NAME,
+            &BISHOP_MAGICS,
+            S::bishop_attacks,
+            FancyMagic::bishop_attacks,
+        );
+    }
+    #[test]
+    fn hyperbola() {
+        matches_magic::<Hyperbola>();
+    }
+    #[test]
+    fn kogge_stone() {
+        matches_magic::<KoggeStone>();
+    }
+    #[test]
+    fn classical() {
+        matches_magic::<Classical>();
+    }
+    #[test]
+    fn sliding_loop() {
+        matches_magic::<Loop>();
+    }
+    #[test]
+    fn pext() {
+        matches_magic::<Pext>();
+    }
+    // without BMI2, Pext and the attacks functions fall back to the magics
+    #[cfg(not(all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2")))]
+    #[test]
+    fn pext_fallback() {
+        assert_eq!(Pext::NAME, "pext (magic)");
+        let occupied = BB_RANK_2 | BB_RANK_7 | Square::D5.as_bb();
+        for sq in Square::all() {
+            assert_eq!(
+                Pext::queen_attacks(sq, occupied),
+                FancyMagic::queen_attacks(sq, occupied)
+            );
+        }
+    }
+    // attacks::rook_attacks and friends agree with the const magic lookups
+    // whichever backend they were compiled with
+    #[test]
+    fn dispatch() {
+        matches(
+            "attacks",
+            &ROOK_MAGICS,
+            crate::attacks::rook_attacks,
+            crate::attacks::rook_attacks_magic,
+        );
+        matches(
+            "attacks",
+            &BISHOP_MAGICS,
+            crate::attacks::bishop_attacks,
+            crate::attacks::bishop_attacks_magic,
+        );
+    }
+    #[test]
+    fn queen_attacks() {
+        let occupied = BB_RANK_2 | BB_RANK_7;
+        assert_eq!(
+            Hyperbola::queen_attacks(Square::D4, occupied),
+            crate::attacks::queen_attacks(Square::D4, occupied)
+        );
+    }
+}
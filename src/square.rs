@@ -29,20 +29,25 @@ impl Square {
             panic!()
         }
     }
+    /// # Safety
+    /// `inner` must be less than 64.
     pub const unsafe fn new_unchecked(inner: u8) -> Self {
         debug_assert!(inner < 64);
         unsafe { std::mem::transmute(inner) }
     }
+    #[must_use]
     pub const fn flip_rank(self) -> Self {
         // SAFETY: given the precondition that `self as u8` is less than 64,
         // this operation cannot construct a value >= 64.
         unsafe { std::mem::transmute(self as u8 ^ 0b111_000) }
     }
+    #[must_use]
     pub const fn flip_file(self) -> Self {
         // SAFETY: given the precondition that `self as u8` is less than 64,
         // this operation cannot construct a value >= 64.
         unsafe { std::mem::transmute(self as u8 ^ 0b000_111) }
     }
+    #[must_use]
     pub const fn relative_to(self, side: Col) -> Self {
         if matches!(side, Col::White) {
             self
@@ -92,10 +97,14 @@ impl Square {
             panic!()
         }
     }
+    /// # Safety
+    /// `self as u8 + offset` must be less than 64.
     pub const unsafe fn add_unchecked(self, offset: u8) -> Self {
         let res = self as u8 + offset;
         unsafe { Self::new_unchecked(res) }
     }
+    /// # Safety
+    /// `offset` must not be greater than `self as u8`.
     pub const unsafe fn sub_unchecked(self, offset: u8) -> Self {
         let res = self as u8 - offset;
         unsafe { Self::new_unchecked(res) }
@@ -109,7 +118,7 @@ impl Square {
         }
     }
     pub const fn as_bb(self) -> BitBoard {
-        1u64 << self.index()
+        BitBoard::from_square(self)
     }
     pub fn pawn_push(self, side: Col) -> Option<Self> {
         if side == Col::White {
@@ -171,7 +180,7 @@ impl Display for Square {
     }
 }
 impl FromStr for Square {
-    type Err = &'static str;
+    type Err = ParseSquareError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         SQUARE_NAMES
@@ -179,9 +188,21 @@ impl FromStr for Square {
             .position(|&name| name == s)
             .and_then(|index| -> Option<u8> { index.try_into().ok() })
             .and_then(Self::new)
-            .ok_or("Invalid square name")
+            .ok_or_else(|| ParseSquareError(s.to_string()))
     }
 }
+impl ParseSquareError {
+    // the text that failed to parse
+    pub fn input(&self) -> &str {
+        &self.0
+    }
+}
+impl Display for ParseSquareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid square name {:?}", self.0)
+    }
+}
+impl std::error::Error for ParseSquareError {}
 impl From<Square> for u16 {
     fn from(square: Square) -> Self {
         square as Self
@@ -203,7 +224,10 @@ mod tests {
     fn from_str() {
         let square = Square::from_str("b3").unwrap();
         assert_eq!(square, Square::B3);
-        assert!(Square::from_str("invalid").is_err());
+        let err = Square::from_str("invalid").unwrap_err();
+        assert_eq!(err, ParseSquareError("invalid".to_string()));
+        assert_eq!(err.input(), "invalid");
+        assert_eq!(err.to_string(), "invalid square name \"invalid\"");
     }
     #[test]
     fn from_rank_file() {
@@ -289,7 +313,7 @@ mod tests {
     fn as_bb() {
         let square = Square::B3;
         let bb = square.as_bb();
-        assert_eq!(bb, 1u64 << 17);
+        assert_eq!(bb, BitBoard(1u64 << 17));
     }
     #[test]
     fn pawn_push() {
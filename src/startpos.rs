@@ -0,0 +1,546 @@
+use crate::consts::*;
+
+pub const CHESS960_POSITIONS: u16 = 960;
+pub const CHESS960_CLASSICAL_INDEX: u16 = 518;
+pub const DOUBLE_CHESS960_POSITIONS: u32 = 960 * 960;
+pub const SHUFFLE_POSITIONS: u16 = 5040;
+
+const SHUFFLE_PIECES: [PieceType; 5] = [
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+    PieceType::King,
+];
+const SHUFFLE_COUNTS: [u32; 5] = [2, 2, 2, 1, 1];
+
+const KNIGHT_PLACEMENTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+// Decodes a Scharnagl index into a back rank: light-squared bishop,
+// dark-squared bishop, queen, knights, then rook, king, rook in the gaps.
+pub const fn chess960_back_rank(index: u16) -> Option<[PieceType; 8]> {
+    if index >= CHESS960_POSITIONS {
+        return None;
+    }
+    let mut rank = [None; 8];
+    let n = index as usize;
+    rank[(n % 4) * 2 + 1] = Some(PieceType::Bishop);
+    let n = n / 4;
+    rank[(n % 4) * 2] = Some(PieceType::Bishop);
+    let n = n / 4;
+    place_nth_empty(&mut rank, n % 6, PieceType::Queen);
+    let (first, second) = KNIGHT_PLACEMENTS[n / 6];
+    // place the later knight first so the earlier one's gap index is unchanged
+    place_nth_empty(&mut rank, second, PieceType::Knight);
+    place_nth_empty(&mut rank, first, PieceType::Knight);
+    place_nth_empty(&mut rank, 0, PieceType::Rook);
+    place_nth_empty(&mut rank, 0, PieceType::King);
+    place_nth_empty(&mut rank, 0, PieceType::Rook);
+    let mut back_rank = [PieceType::Pawn; 8];
+    let mut i = 0;
+    while i < 8 {
+        back_rank[i] = match rank[i] {
+            Some(pt) => pt,
+            None => unreachable!(),
+        };
+        i += 1;
+    }
+    Some(back_rank)
+}
+pub fn chess960_index(back_rank: [PieceType; 8]) -> Option<u16> {
+    let bishops: Vec<usize> = (0..8)
+        .filter(|&f| back_rank[f] == PieceType::Bishop)
+        .collect();
+    if bishops.len() != 2 || bishops[0] % 2 == bishops[1] % 2 {
+        return None;
+    }
+    let (dark, light) = if bishops[0].is_multiple_of(2) {
+        (bishops[0], bishops[1])
+    } else {
+        (bishops[1], bishops[0])
+    };
+    let mut rest: Vec<PieceType> = back_rank
+        .into_iter()
+        .filter(|&pt| pt != PieceType::Bishop)
+        .collect();
+    let queen = rest.iter().position(|&pt| pt == PieceType::Queen)?;
+    rest.remove(queen);
+    let knights: Vec<usize> = (0..rest.len())
+        .filter(|&i| rest[i] == PieceType::Knight)
+        .collect();
+    let [first, second] = knights[..] else {
+        return None;
+    };
+    let knights = KNIGHT_PLACEMENTS
+        .iter()
+        .position(|&placement| placement == (first, second))?;
+    rest.retain(|&pt| pt != PieceType::Knight);
+    if rest != [PieceType::Rook, PieceType::King, PieceType::Rook] {
+        return None;
+    }
+    Some((light / 2 + 4 * (dark / 2 + 4 * (queen + 6 * knights))) as u16)
+}
+// Shuffle chess positions are numbered by their rank among all distinct
+// arrangements of RNBQKBNR, in the order of SHUFFLE_PIECES.
+pub fn shuffle_back_rank(index: u16) -> Option<[PieceType; 8]> {
+    if index >= SHUFFLE_POSITIONS {
+        return None;
+    }
+    let mut counts = SHUFFLE_COUNTS;
+    let mut index = u32::from(index);
+    let mut back_rank = [PieceType::Pawn; 8];
+    for slot in &mut back_rank {
+        for (i, &pt) in SHUFFLE_PIECES.iter().enumerate() {
+            if counts[i] == 0 {
+                continue;
+            }
+            counts[i] -= 1;
+            let n = arrangements(&counts);
+            if index < n {
+                *slot = pt;
+                break;
+            }
+            index -= n;
+            counts[i] += 1;
+        }
+    }
+    Some(back_rank)
+}
+pub fn shuffle_index(back_rank: [PieceType; 8]) -> Option<u16> {
+    let mut counts = SHUFFLE_COUNTS;
+    let mut index = 0;
+    for pt in back_rank {
+        let i = SHUFFLE_PIECES.iter().position(|&p| p == pt)?;
+        if counts[i] == 0 {
+            return None;
+        }
+        for j in 0..i {
+            if counts[j] > 0 {
+                counts[j] -= 1;
+                index += arrangements(&counts);
+                counts[j] += 1;
+            }
+        }
+        counts[i] -= 1;
+    }
+    Some(index as u16)
+}
+fn arrangements(counts: &[u32; 5]) -> u32 {
+    let factorial = |n: u32| (1..=n).product::<u32>();
+    let total = factorial(counts.iter().sum());
+    counts.iter().fold(total, |acc, &c| acc / factorial(c))
+}
+const fn place_nth_empty(rank: &mut [Option<PieceType>; 8], n: usize, piecetype: PieceType) {
+    let mut seen = 0;
+    let mut i = 0;
+    while i < 8 {
+        if rank[i].is_none() {
+            if seen == n {
+                rank[i] = Some(piecetype);
+                return;
+            }
+            seen += 1;
+        }
+        i += 1;
+    }
+    panic!("no empty square left on the back rank");
+}
+
+impl Board {
+    /// A start position with the given back ranks behind full pawn rows.
+    ///
+    /// ```
+    /// use tuatara::{Board, PieceType};
+    /// use PieceType::{Bishop as B, King as K, Knight as N, Queen as Q, Rook as R};
+    ///
+    /// let classical = [R, N, B, Q, K, B, N, R];
+    /// assert_eq!(Board::from_back_ranks(classical, classical), Board::new());
+    /// ```
+    pub fn from_back_ranks(white: [PieceType; 8], black: [PieceType; 8]) -> Self {
+        let mut board = Board::empty();
+        for file in File::all() {
+            board.set_piece_at(
+                Square::from_rank_file(Rank::One, file),
+                Piece::new(Col::White, white[file]),
+            );
+            board.set_piece_at(
+                Square::from_rank_file(Rank::Two, file),
+                Piece::new(Col::White, PieceType::Pawn),
+            );
+            board.set_piece_at(
+                Square::from_rank_file(Rank::Seven, file),
+                Piece::new(Col::Black, PieceType::Pawn),
+            );
+            board.set_piece_at(
+                Square::from_rank_file(Rank::Eight, file),
+                Piece::new(Col::Black, black[file]),
+            );
+        }
+        board
+    }
+    pub fn chess960(index: u16) -> Option<Self> {
+        let back_rank = chess960_back_rank(index)?;
+        Some(Self::from_back_ranks(back_rank, back_rank))
+    }
+}
+
+impl StartPos {
+    pub fn back_ranks(self) -> Option<([PieceType; 8], [PieceType; 8])> {
+        match self {
+            StartPos::Classical => {
+                chess960_back_rank(CHESS960_CLASSICAL_INDEX).map(|rank| (rank, rank))
+            }
+            StartPos::Chess960(index) => chess960_back_rank(index).map(|rank| (rank, rank)),
+            StartPos::DoubleChess960(index) => {
+                if index >= DOUBLE_CHESS960_POSITIONS {
+                    return None;
+                }
+                let white = chess960_back_rank((index / 960) as u16)?;
+                let black = chess960_back_rank((index % 960) as u16)?;
+                Some((white, black))
+            }
+            StartPos::Shuffle(index) => shuffle_back_rank(index).map(|rank| (rank, rank)),
+        }
+    }
+    /// The board and castling rights of a start position, or `None` for an
+    /// out of range index.
+    ///
+    /// ```
+    /// use tuatara::{Board, CastlingRights, StartPos};
+    ///
+    /// let (board, rights) = StartPos::Chess960(518).setup().unwrap();
+    /// assert_eq!(board, Board::new());
+    /// assert_eq!(rights, CastlingRights::CLASSICAL);
+    /// assert_eq!(StartPos::from_board(&board, &rights), Some(StartPos::Classical));
+    /// assert_eq!(StartPos::Chess960(960).setup(), None);
+    /// ```
+    pub fn setup(self) -> Option<(Board, CastlingRights)> {
+        let (white, black) = self.back_ranks()?;
+        let board = Board::from_back_ranks(white, black);
+        let mut rights = CastlingRights::from_board(&board);
+        // shuffle chess only castles with king and rook on their classical squares
+        if let StartPos::Shuffle(_) = self {
+            for col in Col::all() {
+                let king = Piece::new(col, PieceType::King);
+                let king_home = board.piece_at(Square::E1.relative_to(col)) == Some(king);
+                for side in CastlingSide::all() {
+                    if !king_home
+                        || rights.rook(col, side) != CastlingRights::CLASSICAL.rook(col, side)
+                    {
+                        rights.remove(col, side);
+                    }
+                }
+            }
+        }
+        Some((board, rights))
+    }
+    pub fn board(self) -> Option<Board> {
+        self.setup().map(|(board, _)| board)
+    }
+    // Recovers the start position a board and its castling rights were set
+    // up from, preferring the most specific description when several apply.
+    // Every chess960 back rank is also a shuffle chess arrangement, and only
+    // the castling rights tell the two apart. A double chess960 position
+    // with the same back rank for both sides comes back as Chess960 (or
+    // Classical); to_double_chess960 recovers that form.
+    pub fn from_board(board: &Board, castling: &CastlingRights) -> Option<Self> {
+        if board.by_piece(Piece::WP) != BB_RANK_2
+            || board.by_piece(Piece::BP) != BB_RANK_7
+            || board.occupied() != BB_RANK_1 | BB_RANK_2 | BB_RANK_7 | BB_RANK_8
+            || board.white() != BB_RANK_1 | BB_RANK_2
+        {
+            return None;
+        }
+        let mut white = [PieceType::Pawn; 8];
+        let mut black = [PieceType::Pawn; 8];
+        for file in File::all() {
+            white[file] = board.piecetype_at(Square::from_rank_file(Rank::One, file))?;
+            black[file] = board.piecetype_at(Square::from_rank_file(Rank::Eight, file))?;
+        }
+        let (chess960, shuffle) = if white == black {
+            let chess960 = match chess960_index(white) {
+                Some(CHESS960_CLASSICAL_INDEX) => Some(StartPos::Classical),
+                index => index.map(StartPos::Chess960),
+            };
+            (chess960, shuffle_index(white).map(StartPos::Shuffle))
+        } else {
+            let double = chess960_index(white)
+                .zip(chess960_index(black))
+                .map(|(white, black)| {
+                    StartPos::DoubleChess960(u32::from(white) * 960 + u32::from(black))
+                });
+            (double, None)
+        };
+        [chess960, shuffle]
+            .into_iter()
+            .flatten()
+            .find(|start| start.setup().is_some_and(|(_, rights)| rights == *castling))
+    }
+    // The same start position numbered as a double chess960 one.
+    pub const fn to_double_chess960(self) -> Option<Self> {
+        let index = match self {
+            StartPos::Classical => CHESS960_CLASSICAL_INDEX as u32 * 961,
+            StartPos::Chess960(index) if index < CHESS960_POSITIONS => index as u32 * 961,
+            StartPos::DoubleChess960(index) if index < DOUBLE_CHESS960_POSITIONS => index,
+            _ => return None,
+        };
+        Some(StartPos::DoubleChess960(index))
+    }
+    pub fn random_chess960(rng: &mut Prng) -> Self {
+        StartPos::Chess960(rng.below(u64::from(CHESS960_POSITIONS)) as u16)
+    }
+    pub fn random_double_chess960(rng: &mut Prng) -> Self {
+        StartPos::DoubleChess960(rng.below(u64::from(DOUBLE_CHESS960_POSITIONS)) as u32)
+    }
+    pub fn random_shuffle(rng: &mut Prng) -> Self {
+        StartPos::Shuffle(rng.below(u64::from(SHUFFLE_POSITIONS)) as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::*;
+    use crate::startpos::{
+        self, CHESS960_CLASSICAL_INDEX, CHESS960_POSITIONS, DOUBLE_CHESS960_POSITIONS,
+        SHUFFLE_POSITIONS,
+    };
+    use std::collections::BTreeSet;
+
+    const R: PieceType = PieceType::Rook;
+    const N: PieceType = PieceType::Knight;
+    const B: PieceType = PieceType::Bishop;
+    const Q: PieceType = PieceType::Queen;
+    const K: PieceType = PieceType::King;
+
+    #[test]
+    fn chess960_back_rank() {
+        assert_eq!(
+            startpos::chess960_back_rank(CHESS960_CLASSICAL_INDEX),
+            Some([R, N, B, Q, K, B, N, R])
+        );
+        assert_eq!(
+            startpos::chess960_back_rank(0),
+            Some([B, B, Q, N, N, R, K, R])
+        );
+        assert_eq!(
+            startpos::chess960_back_rank(959),
+            Some([R, K, R, N, N, Q, B, B])
+        );
+        assert_eq!(startpos::chess960_back_rank(CHESS960_POSITIONS), None);
+    }
+    #[test]
+    fn chess960_back_rank_all() {
+        let mut seen = BTreeSet::new();
+        for index in 0..CHESS960_POSITIONS {
+            let rank = startpos::chess960_back_rank(index).unwrap();
+            let files =
+                |pt: PieceType| -> Vec<usize> { (0..8).filter(|&f| rank[f] == pt).collect() };
+            let bishops = files(B);
+            assert_eq!(bishops.len(), 2);
+            assert_ne!(bishops[0] % 2, bishops[1] % 2);
+            let rooks = files(R);
+            let king = files(K)[0];
+            assert!(rooks[0] < king && king < rooks[1]);
+            assert_eq!(files(N).len(), 2);
+            assert_eq!(files(Q).len(), 1);
+            assert!(seen.insert(rank));
+        }
+    }
+    #[test]
+    fn from_back_ranks() {
+        let board = Board::from_back_ranks([R, N, B, Q, K, B, N, R], [R, N, B, Q, K, B, N, R]);
+        assert_eq!(board, Board::new());
+    }
+    #[test]
+    fn chess960() {
+        assert_eq!(
+            Board::chess960(CHESS960_CLASSICAL_INDEX),
+            Some(Board::new())
+        );
+        let board = Board::chess960(0).unwrap();
+        assert_eq!(board.piece_at(Square::A1), Some(Piece::WB));
+        assert_eq!(board.piece_at(Square::G8), Some(Piece::BK));
+        assert_eq!(board.king_of(Col::White), Some(Square::G1));
+        assert_eq!(CastlingRights::from_board(&board).to_shredder(), "HFhf");
+        assert_eq!(Board::chess960(960), None);
+    }
+    #[test]
+    fn chess960_index() {
+        for index in 0..CHESS960_POSITIONS {
+            let rank = startpos::chess960_back_rank(index).unwrap();
+            assert_eq!(startpos::chess960_index(rank), Some(index));
+        }
+        assert_eq!(startpos::chess960_index([K, R, B, Q, R, B, N, N]), None);
+        assert_eq!(startpos::chess960_index([R, N, B, Q, B, K, N, R]), None);
+        assert_eq!(startpos::chess960_index([R, N, B, Q, Q, B, N, R]), None);
+    }
+    #[test]
+    fn shuffle_back_rank() {
+        let mut seen = BTreeSet::new();
+        for index in 0..SHUFFLE_POSITIONS {
+            let rank = startpos::shuffle_back_rank(index).unwrap();
+            assert_eq!(startpos::shuffle_index(rank), Some(index));
+            assert!(seen.insert(rank));
+        }
+        assert_eq!(
+            startpos::shuffle_back_rank(0),
+            Some([N, N, B, B, R, R, Q, K])
+        );
+        assert_eq!(
+            startpos::shuffle_back_rank(SHUFFLE_POSITIONS - 1),
+            Some([K, Q, R, R, B, B, N, N])
+        );
+        assert_eq!(startpos::shuffle_back_rank(SHUFFLE_POSITIONS), None);
+        assert_eq!(startpos::shuffle_index([R, N, B, Q, K, B, N, N]), None);
+    }
+    #[test]
+    fn setup() {
+        let (board, rights) = StartPos::Classical.setup().unwrap();
+        assert_eq!(board, Board::new());
+        assert_eq!(rights, CastlingRights::CLASSICAL);
+        let (board, rights) = StartPos::Chess960(0).setup().unwrap();
+        assert_eq!(board, Board::chess960(0).unwrap());
+        assert_eq!(rights.to_shredder(), "HFhf");
+        let (board, rights) = StartPos::DoubleChess960(959).setup().unwrap();
+        assert_eq!(board.piece_at(Square::A1), Some(Piece::WB));
+        assert_eq!(board.piece_at(Square::A8), Some(Piece::BR));
+        assert_eq!(rights.to_shredder(), "HFca");
+        assert_eq!(
+            StartPos::DoubleChess960(DOUBLE_CHESS960_POSITIONS).setup(),
+            None
+        );
+        assert_eq!(StartPos::Chess960(CHESS960_POSITIONS).setup(), None);
+        let (_, rights) = StartPos::Shuffle(0).setup().unwrap();
+        assert!(rights.is_empty());
+        let classical = startpos::shuffle_index([R, N, B, Q, K, B, N, R]).unwrap();
+        let (board, rights) = StartPos::Shuffle(classical).setup().unwrap();
+        assert_eq!(board, Board::new());
+        assert_eq!(rights, CastlingRights::CLASSICAL);
+        let index = startpos::shuffle_index([R, N, B, K, Q, B, N, R]).unwrap();
+        let (_, rights) = StartPos::Shuffle(index).setup().unwrap();
+        assert!(rights.is_empty());
+    }
+    #[test]
+    fn from_board() {
+        assert_eq!(
+            StartPos::from_board(&Board::new(), &CastlingRights::CLASSICAL),
+            Some(StartPos::Classical)
+        );
+        for start in [
+            StartPos::Chess960(0),
+            StartPos::Chess960(959),
+            StartPos::DoubleChess960(123_456),
+            StartPos::Shuffle(0),
+            StartPos::Shuffle(4321),
+        ] {
+            let (board, rights) = start.setup().unwrap();
+            assert_eq!(StartPos::from_board(&board, &rights), Some(start));
+        }
+        let (board, rights) = StartPos::DoubleChess960(518 * 960 + 518).setup().unwrap();
+        assert_eq!(
+            StartPos::from_board(&board, &rights),
+            Some(StartPos::Classical)
+        );
+        // a chess960 back rank without castling is a shuffle chess position
+        let index = startpos::shuffle_index([R, N, B, K, Q, B, N, R]).unwrap();
+        let (board, rights) = StartPos::Shuffle(index).setup().unwrap();
+        assert_eq!(
+            StartPos::from_board(&board, &rights),
+            Some(StartPos::Shuffle(index))
+        );
+        assert_eq!(
+            StartPos::from_board(&board, &CastlingRights::from_board(&board)),
+            Some(StartPos::Chess960(534))
+        );
+        // no start position leaves the classical board without castling
+        assert_eq!(
+            StartPos::from_board(&Board::new(), &CastlingRights::default()),
+            None
+        );
+        let mut board = Board::new();
+        board.discard_piece_at(Square::E2);
+        assert_eq!(
+            StartPos::from_board(&board, &CastlingRights::CLASSICAL),
+            None
+        );
+        let white = startpos::shuffle_back_rank(0).unwrap();
+        let board = Board::from_back_ranks(white, [R, N, B, Q, K, B, N, R]);
+        assert_eq!(
+            StartPos::from_board(&board, &CastlingRights::from_board(&board)),
+            None
+        );
+    }
+    // the recovered start position sets up the same board and rights
+    #[test]
+    fn from_board_round_trip() {
+        let recover = |start: StartPos| {
+            let (board, rights) = start.setup().unwrap();
+            StartPos::from_board(&board, &rights)
+        };
+        assert_eq!(recover(StartPos::Classical), Some(StartPos::Classical));
+        for index in 0..CHESS960_POSITIONS {
+            let expected = if index == CHESS960_CLASSICAL_INDEX {
+                StartPos::Classical
+            } else {
+                StartPos::Chess960(index)
+            };
+            assert_eq!(recover(StartPos::Chess960(index)), Some(expected));
+        }
+        let start = StartPos::DoubleChess960(123_456);
+        assert_eq!(recover(start), Some(start));
+        // identical back ranks come back in their single chess960 form
+        let start = StartPos::DoubleChess960(5 * 960 + 5);
+        assert_eq!(recover(start), Some(StartPos::Chess960(5)));
+        assert_eq!(StartPos::Chess960(5).to_double_chess960(), Some(start));
+        let start = StartPos::DoubleChess960(u32::from(CHESS960_CLASSICAL_INDEX) * 961);
+        assert_eq!(recover(start), Some(StartPos::Classical));
+        assert_eq!(StartPos::Classical.to_double_chess960(), Some(start));
+        assert_eq!(StartPos::Shuffle(0).to_double_chess960(), None);
+        assert_eq!(StartPos::Chess960(960).to_double_chess960(), None);
+        // the 18 chess960 arrangements with king and rooks on their classical
+        // squares have the same castling rights in both variants
+        let mut promoted = 0;
+        for index in 0..SHUFFLE_POSITIONS {
+            let start = StartPos::Shuffle(index);
+            let recovered = recover(start).unwrap();
+            if recovered != start {
+                let back_rank = startpos::shuffle_back_rank(index).unwrap();
+                let chess960 = startpos::chess960_index(back_rank).unwrap();
+                assert_eq!(Some(recovered), recover(StartPos::Chess960(chess960)));
+                promoted += 1;
+            }
+        }
+        assert_eq!(promoted, 18);
+    }
+    #[test]
+    fn random() {
+        let mut rng = Prng::new(2024);
+        for _ in 0..100 {
+            let start = StartPos::random_chess960(&mut rng);
+            assert!(start.board().is_some());
+            let start = StartPos::random_double_chess960(&mut rng);
+            let (board, rights) = start.setup().unwrap();
+            let recovered = StartPos::from_board(&board, &rights).unwrap();
+            assert_eq!(recovered.to_double_chess960(), Some(start));
+            let start = StartPos::random_shuffle(&mut rng);
+            assert!(start.board().is_some());
+        }
+        let mut a = Prng::new(9);
+        let mut b = Prng::new(9);
+        assert_eq!(
+            StartPos::random_shuffle(&mut a),
+            StartPos::random_shuffle(&mut b)
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use std::fmt::Write;
+
+use crate::consts::*;
+
+const SQUARE_SIZE: u32 = 45;
+const MARGIN: u32 = 15;
+const LIGHT: &str = "#f0d9b5";
+const DARK: &str = "#b58863";
+const HIGHLIGHT: &str = "#cdd16a";
+const ARROW: &str = "#15781b";
+const ARROW_WIDTH: f64 = 7.0;
+// the arrowhead is drawn as a marker scaled by the stroke width
+const ARROW_HEAD: f64 = 2.5;
+
+// Piece outlines drawn on a 45x45 square. They carry no fill of their own,
+// so one path serves both colours.
+const PIECE_PATHS: [(&str, &str); 6] = [
+    (
+        "pawn",
+        "M17.5 14a5 5 0 1 1 10 0a5 5 0 1 1-10 0zM18 21h9l-1.5 4c3.5 2 5.5 6 5.5 10H14c0-4 2-8 5.5-10zM12 35h21v4H12z",
+    ),
+    (
+        "knight",
+        "M12 35h21v4H12zM15 35c0-8 7-10 8-16-3 2-5 4-8 3l-2-2c2-5 5-8 8-11l1-3 2 3c6 1 9 7 9 14v12z",
+    ),
+    (
+        "bishop",
+        "M11 35h23v4H11zM15 35c0-7 3-12 7.5-17 4.5 5 7.5 10 7.5 17zM20.5 12a2 2 0 1 1 4 0a2 2 0 1 1-4 0z",
+    ),
+    (
+        "rook",
+        "M11 35h23v4H11zM14.5 35l1.5-16h13l1.5 16zM12 19v-8h4v3h4v-3h5v3h4v-3h4v8z",
+    ),
+    (
+        "queen",
+        "M11 35h23v4H11zM13 35l-3-18 6.5 9 2-13 4 12 4-12 2 13 6.5-9-3 18z",
+    ),
+    (
+        "king",
+        "M11 35h23v4H11zM13 35c-2-7 2-13 9.5-13s11.5 6 9.5 13zM21 6h3v4h4v3h-4v8h-3v-8h-4v-3h4z",
+    ),
+];
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            orientation: Col::White,
+            coordinates: true,
+            size: 390,
+            highlights: BB_EMPTY,
+            arrows: Vec::new(),
+            check: None,
+        }
+    }
+}
+
+impl Board {
+    // A self-contained SVG diagram. Identical boards and options always give
+    // byte-identical output, so it can be used for snapshot tests.
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let margin = if options.coordinates { MARGIN } else { 0 };
+        let extent = 8 * SQUARE_SIZE + 2 * margin;
+        let mut s = String::new();
+        writeln!(
+            s,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 {extent} {extent}" width="{size}" height="{size}">"#,
+            size = options.size
+        )
+        .unwrap();
+        write_defs(&mut s);
+
+        let origin = |square: Square| {
+            let (col, row) = match options.orientation {
+                Col::White => (square.file() as u32, 7 - square.rank() as u32),
+                Col::Black => (7 - square.file() as u32, square.rank() as u32),
+            };
+            (margin + col * SQUARE_SIZE, margin + row * SQUARE_SIZE)
+        };
+        for square in Square::all() {
+            let (x, y) = origin(square);
+            let fill = if BB_LIGHT_SQUARES.contains_square(square) {
+                LIGHT
+            } else {
+                DARK
+            };
+            writeln!(
+                s,
+                r#"<rect x="{x}" y="{y}" width="{SQUARE_SIZE}" height="{SQUARE_SIZE}" fill="{fill}"/>"#
+            )
+            .unwrap();
+        }
+        for square in options.highlights {
+            let (x, y) = origin(square);
+            writeln!(
+                s,
+                r#"<rect x="{x}" y="{y}" width="{SQUARE_SIZE}" height="{SQUARE_SIZE}" fill="{HIGHLIGHT}" fill-opacity="0.8"/>"#
+            )
+            .unwrap();
+        }
+        if let Some(square) = options.check {
+            let (x, y) = origin(square);
+            writeln!(
+                s,
+                r#"<rect x="{x}" y="{y}" width="{SQUARE_SIZE}" height="{SQUARE_SIZE}" fill="url(#check)"/>"#
+            )
+            .unwrap();
+        }
+        // SVG 2 viewers read href, older ones (and some converters) only
+        // xlink:href
+        for square in self.occupied {
+            let piece = self.piece_at(square).unwrap();
+            let (x, y) = origin(square);
+            let (name, _) = PIECE_PATHS[piece.piecetype().index()];
+            let fill = match piece.col() {
+                Col::White => "#fff",
+                Col::Black => "#000",
+            };
+            writeln!(
+                s,
+                r##"<use href="#{name}" xlink:href="#{name}" x="{x}" y="{y}" fill="{fill}" stroke="#000" stroke-width="1.5" stroke-linejoin="round"/>"##
+            )
+            .unwrap();
+        }
+        let centre = |square: Square| {
+            let (x, y) = origin(square);
+            let half = f64::from(SQUARE_SIZE) / 2.0;
+            (f64::from(x) + half, f64::from(y) + half)
+        };
+        for &(from, to) in &options.arrows {
+            write_arrow(&mut s, centre(from), centre(to));
+        }
+        if options.coordinates {
+            write_coordinates(&mut s, options.orientation);
+        }
+        s.push_str("</svg>\n");
+        s
+    }
+}
+
+fn write_defs(s: &mut String) {
+    s.push_str("<defs>\n");
+    for (name, path) in PIECE_PATHS {
+        writeln!(s, r#"<path id="{name}" d="{path}"/>"#).unwrap();
+    }
+    s.push_str(concat!(
+        r#"<radialGradient id="check">"#,
+        r##"<stop offset="0%" stop-color="#ff0000"/>"##,
+        r##"<stop offset="50%" stop-color="#e70000"/>"##,
+        r##"<stop offset="100%" stop-color="#9e0000" stop-opacity="0"/>"##,
+        "</radialGradient>\n",
+    ));
+    writeln!(
+        s,
+        r#"<marker id="arrowhead" viewBox="0 0 10 10" refX="0" refY="5" markerWidth="{ARROW_HEAD}" markerHeight="{ARROW_HEAD}" orient="auto"><path d="M0 0L10 5L0 10z" fill="{ARROW}"/></marker>"#
+    )
+    .unwrap();
+    s.push_str("</defs>\n");
+}
+
+// The line stops short of the target centre by the length of the head,
+// so that the tip of the head lands on the centre. An arrow from a square
+// to itself is drawn as a ring.
+fn write_arrow(s: &mut String, (x1, y1): (f64, f64), (x2, y2): (f64, f64)) {
+    let (dx, dy) = (x2 - x1, y2 - y1);
+    let length = dx.hypot(dy);
+    if length == 0.0 {
+        writeln!(
+            s,
+            r#"<circle cx="{x1:.1}" cy="{y1:.1}" r="19" fill="none" stroke="{ARROW}" stroke-opacity="0.8" stroke-width="{ARROW_WIDTH:.1}"/>"#
+        )
+        .unwrap();
+        return;
+    }
+    let head = ARROW_HEAD * ARROW_WIDTH;
+    let (x2, y2) = (x2 - dx / length * head, y2 - dy / length * head);
+    writeln!(
+        s,
+        r#"<line x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}" stroke="{ARROW}" stroke-opacity="0.8" stroke-width="{ARROW_WIDTH:.1}" stroke-linecap="round" marker-end="url(#arrowhead)"/>"#
+    )
+    .unwrap();
+}
+
+fn write_coordinates(s: &mut String, orientation: Col) {
+    let font = r##"font-family="sans-serif" font-size="10" fill="#333" text-anchor="middle""##;
+    let half = SQUARE_SIZE / 2;
+    for i in 0..8u32 {
+        let (file, rank) = match orientation {
+            Col::White => (i, 7 - i),
+            Col::Black => (7 - i, i),
+        };
+        let file = (b'a' + file as u8) as char;
+        let rank = rank + 1;
+        let along = MARGIN + i * SQUARE_SIZE + half;
+        let below = MARGIN + 8 * SQUARE_SIZE + 11;
+        let left = MARGIN / 2;
+        writeln!(s, r#"<text x="{along}" y="{below}" {font}>{file}</text>"#).unwrap();
+        writeln!(
+            s,
+            r#"<text x="{left}" y="{}" {font}>{rank}</text>"#,
+            along + 4
+        )
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::*;
+
+    #[test]
+    fn deterministic() {
+        let options = SvgOptions {
+            highlights: Square::E2.as_bb() | Square::E4.as_bb(),
+            arrows: vec![(Square::G1, Square::F3)],
+            ..SvgOptions::default()
+        };
+        let board = Board::new();
+        let svg = board.to_svg(&options);
+        assert_eq!(svg, board.to_svg(&options.clone()));
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 390 390" width="390" height="390">"#));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<use ").count(), 32);
+        assert_eq!(svg.matches(r#"fill-opacity="0.8""#).count(), 2);
+        assert_eq!(svg.matches("<line ").count(), 1);
+        assert_eq!(svg.matches("<text ").count(), 16);
+        // every piece shape is embedded, nothing is fetched
+        assert_eq!(svg.matches("<path id=").count(), 6);
+        // each piece links its shape both ways
+        assert_eq!(svg.matches(" xlink:href=").count(), 32);
+    }
+    #[test]
+    fn start_position_snapshot() {
+        let svg = Board::new().to_svg(&SvgOptions::default());
+        assert_eq!(svg, include_str!("testdata/start_position.svg"));
+    }
+    #[test]
+    fn orientation() {
+        let mut board = Board::empty();
+        board.set_piece_at(Square::A1, Piece::WK);
+        let options = SvgOptions {
+            coordinates: false,
+            ..SvgOptions::default()
+        };
+        let svg = board.to_svg(&options);
+        assert!(svg.contains(r#"viewBox="0 0 360 360""#));
+        assert!(!svg.contains("<text "));
+        assert!(
+            svg.contains(r##"<use href="#king" xlink:href="#king" x="0" y="315" fill="#fff""##)
+        );
+        let options = SvgOptions {
+            orientation: Col::Black,
+            ..options
+        };
+        let svg = board.to_svg(&options);
+        assert!(
+            svg.contains(r##"<use href="#king" xlink:href="#king" x="315" y="0" fill="#fff""##)
+        );
+        // a1 is dark whichever way up the board is
+        assert!(svg.contains(r##"<rect x="315" y="0" width="45" height="45" fill="#b58863"/>"##));
+    }
+    #[test]
+    fn check_and_arrows() {
+        let mut board = Board::empty();
+        board.set_piece_at(Square::E8, Piece::BK);
+        let options = SvgOptions {
+            coordinates: false,
+            check: Some(Square::E8),
+            arrows: vec![(Square::E1, Square::E8), (Square::D4, Square::D4)],
+            ..SvgOptions::default()
+        };
+        let svg = board.to_svg(&options);
+        assert!(svg.contains(r#"<rect x="180" y="0" width="45" height="45" fill="url(#check)"/>"#));
+        // the head is 17.5 long and its tip sits on the centre of e8
+        assert!(svg.contains(r#"<line x1="202.5" y1="337.5" x2="202.5" y2="40.0""#));
+        assert!(svg.contains(r#"<circle cx="157.5" cy="202.5" r="19""#));
+    }
+}
@@ -0,0 +1,336 @@
+use std::fmt;
+
+use crate::consts::*;
+
+pub const DEFAULT_MOVE_OVERHEAD: u64 = 30;
+const SUDDEN_DEATH_MOVES: u64 = 40;
+const INCREMENT_MOVES: u64 = 30;
+const MAX_MOVES_TO_GO: u64 = 50;
+const HARD_LIMIT_FACTOR: u64 = 4;
+const INSTABILITY_STEP: u64 = 30;
+const MAX_INSTABILITY: u64 = 4;
+const SCORE_DROP_MARGIN: i32 = 30;
+const SCORE_DROP_BONUS: u64 = 50;
+
+impl Limits {
+    pub fn from_go(line: &str) -> Result<Self, LimitsError> {
+        let mut limits = Limits::default();
+        let mut tokens = line.split_whitespace().peekable();
+        if tokens.peek() == Some(&"go") {
+            tokens.next();
+        }
+        while let Some(token) = tokens.next() {
+            match token {
+                "infinite" => limits.infinite = true,
+                "wtime" => limits.wtime = Some(parse_millis("wtime", tokens.next())?),
+                "btime" => limits.btime = Some(parse_millis("btime", tokens.next())?),
+                "winc" => limits.winc = parse_millis("winc", tokens.next())?,
+                "binc" => limits.binc = parse_millis("binc", tokens.next())?,
+                "movetime" => limits.movetime = Some(parse_millis("movetime", tokens.next())?),
+                "movestogo" => limits.movestogo = Some(parse_count("movestogo", tokens.next())?),
+                "nodes" => limits.nodes = Some(parse_count("nodes", tokens.next())?),
+                "depth" => {
+                    let depth = parse_count("depth", tokens.next())?;
+                    let depth = depth
+                        .try_into()
+                        .map_err(|_| LimitsError::DepthOutOfRange(depth))?;
+                    limits.depth = Some(depth);
+                }
+                // unknown parameters are ignored, as the UCI protocol requires
+                _ => {}
+            }
+        }
+        Ok(limits)
+    }
+    pub const fn time(&self, side: Col) -> Option<u64> {
+        match side {
+            Col::White => self.wtime,
+            Col::Black => self.btime,
+        }
+    }
+    pub const fn inc(&self, side: Col) -> u64 {
+        match side {
+            Col::White => self.winc,
+            Col::Black => self.binc,
+        }
+    }
+}
+
+// GUIs may report a negative clock once a side has flagged, so treat it as zero.
+fn parse_millis(name: &'static str, token: Option<&str>) -> Result<u64, LimitsError> {
+    let value: i64 = parse_value(name, token)?;
+    Ok(value.max(0) as u64)
+}
+fn parse_count(name: &'static str, token: Option<&str>) -> Result<u64, LimitsError> {
+    parse_value(name, token)
+}
+fn parse_value<T: std::str::FromStr>(
+    name: &'static str,
+    token: Option<&str>,
+) -> Result<T, LimitsError> {
+    let value = token.ok_or(LimitsError::MissingValue(name))?;
+    value.parse().map_err(|_| LimitsError::InvalidValue {
+        name,
+        value: value.to_string(),
+    })
+}
+
+// x * num / den without overflow for any clock the parser accepts
+const fn fraction(x: u64, num: u64, den: u64) -> u64 {
+    (x as u128 * num as u128 / den as u128) as u64
+}
+
+impl fmt::Display for LimitsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingValue(name) => write!(f, "missing value for {name}"),
+            Self::InvalidValue { name, value } => write!(f, "invalid value for {name}: {value:?}"),
+            Self::DepthOutOfRange(depth) => write!(f, "depth {depth} out of range"),
+        }
+    }
+}
+impl std::error::Error for LimitsError {}
+
+impl TimeManager {
+    /// Splits the clock of `side` into a soft limit, checked between
+    /// iterations, and a hard limit that aborts the search. `overhead` is
+    /// kept back for communication delays.
+    ///
+    /// ```
+    /// use tuatara::{Col, Limits, TimeControl, TimeManager};
+    ///
+    /// let limits = Limits::from_go("go wtime 60000 btime 60000 winc 1000").unwrap();
+    /// let tm = TimeManager::new(&limits, Col::White, 50, 20);
+    /// assert_eq!(tm.control(), TimeControl::Increment);
+    /// assert!(tm.soft_limit() <= tm.hard_limit());
+    /// assert!(tm.hard_limit() < 60000);
+    /// assert!(!tm.should_stop(0));
+    /// ```
+    pub fn new(limits: &Limits, side: Col, overhead: u64, legal_moves: usize) -> Self {
+        let (control, soft, hard) = Self::allocate(limits, side, overhead);
+        let mut tm = TimeManager {
+            control,
+            soft,
+            hard,
+            scale: 100,
+            instability: 0,
+            last_score: None,
+        };
+        // with a single reply there is nothing to think about, so stop after the first iteration
+        if legal_moves == 1 && control != TimeControl::Infinite {
+            tm.soft = 0;
+        }
+        tm
+    }
+    fn allocate(limits: &Limits, side: Col, overhead: u64) -> (TimeControl, u64, u64) {
+        if limits.infinite {
+            return (TimeControl::Infinite, u64::MAX, u64::MAX);
+        }
+        if let Some(movetime) = limits.movetime {
+            let budget = movetime.saturating_sub(overhead).max(1);
+            return (TimeControl::MoveTime, budget, budget);
+        }
+        let Some(time) = limits.time(side) else {
+            return (TimeControl::Infinite, u64::MAX, u64::MAX);
+        };
+        let inc = limits.inc(side);
+        let available = time.saturating_sub(overhead).max(1);
+        let (control, moves) = match limits.movestogo {
+            Some(mtg) => (TimeControl::Repeating, mtg.clamp(1, MAX_MOVES_TO_GO)),
+            None if inc > 0 => (TimeControl::Increment, INCREMENT_MOVES),
+            None => (TimeControl::SuddenDeath, SUDDEN_DEATH_MOVES),
+        };
+        // on the last move before a time control the whole budget may be used
+        let cap = if moves == 1 {
+            fraction(available, 9, 10)
+        } else {
+            available / 2
+        };
+        let base = (available / moves).saturating_add(fraction(inc, 3, 4));
+        let hard = base.saturating_mul(HARD_LIMIT_FACTOR).min(cap).max(1);
+        let soft = base.min(hard);
+        (control, soft, hard)
+    }
+    pub const fn control(&self) -> TimeControl {
+        self.control
+    }
+    pub const fn soft_limit(&self) -> u64 {
+        let scaled = self.soft as u128 * self.scale as u128 / 100;
+        if scaled < self.hard as u128 {
+            scaled as u64
+        } else {
+            self.hard
+        }
+    }
+    pub const fn hard_limit(&self) -> u64 {
+        self.hard
+    }
+    pub fn update(&mut self, best_move_changed: bool, score: i32) {
+        if best_move_changed {
+            self.instability = (self.instability + 1).min(MAX_INSTABILITY);
+        } else {
+            self.instability = self.instability.saturating_sub(1);
+        }
+        let dropped = self
+            .last_score
+            .is_some_and(|last| last.saturating_sub(score) >= SCORE_DROP_MARGIN);
+        self.scale = 100 + self.instability * INSTABILITY_STEP;
+        if dropped {
+            self.scale += SCORE_DROP_BONUS;
+        }
+        self.last_score = Some(score);
+    }
+    pub const fn should_stop(&self, elapsed: u64) -> bool {
+        elapsed >= self.soft_limit()
+    }
+    pub const fn out_of_time(&self, elapsed: u64) -> bool {
+        elapsed >= self.hard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::*;
+    use crate::timeman::DEFAULT_MOVE_OVERHEAD;
+    #[test]
+    fn from_go() {
+        let limits =
+            Limits::from_go("go wtime 60000 btime -20 winc 1000 binc 500 movestogo 12").unwrap();
+        assert_eq!(limits.wtime, Some(60000));
+        assert_eq!(limits.btime, Some(0));
+        assert_eq!(limits.winc, 1000);
+        assert_eq!(limits.binc, 500);
+        assert_eq!(limits.movestogo, Some(12));
+        assert!(!limits.infinite);
+        let limits = Limits::from_go("depth 7 nodes 5000 ponder infinite").unwrap();
+        assert_eq!(limits.depth, Some(7));
+        assert_eq!(limits.nodes, Some(5000));
+        assert!(limits.infinite);
+        assert_eq!(
+            Limits::from_go("go wtime"),
+            Err(LimitsError::MissingValue("wtime"))
+        );
+        let err = Limits::from_go("go movetime x").unwrap_err();
+        assert_eq!(
+            err,
+            LimitsError::InvalidValue {
+                name: "movetime",
+                value: "x".to_string()
+            }
+        );
+        assert_eq!(err.to_string(), "invalid value for movetime: \"x\"");
+        assert_eq!(
+            Limits::from_go("go depth -1"),
+            Err(LimitsError::InvalidValue {
+                name: "depth",
+                value: "-1".to_string()
+            })
+        );
+        assert_eq!(
+            Limits::from_go("go depth 5000000000"),
+            Err(LimitsError::DepthOutOfRange(5_000_000_000))
+        );
+    }
+    #[test]
+    fn time() {
+        let limits = Limits::from_go("go wtime 100 btime 200 winc 1 binc 2").unwrap();
+        assert_eq!(limits.time(Col::White), Some(100));
+        assert_eq!(limits.time(Col::Black), Some(200));
+        assert_eq!(limits.inc(Col::White), 1);
+        assert_eq!(limits.inc(Col::Black), 2);
+    }
+    #[test]
+    fn new() {
+        let limits = Limits::from_go("go wtime 60000 btime 60000").unwrap();
+        let tm = TimeManager::new(&limits, Col::White, 0, 20);
+        assert_eq!(tm.control(), TimeControl::SuddenDeath);
+        assert_eq!(tm.soft_limit(), 1500);
+        assert_eq!(tm.hard_limit(), 6000);
+        let limits = Limits::from_go("go wtime 60000 btime 60000 winc 1000 binc 1000").unwrap();
+        let tm = TimeManager::new(&limits, Col::Black, 0, 20);
+        assert_eq!(tm.control(), TimeControl::Increment);
+        assert_eq!(tm.soft_limit(), 2750);
+        assert_eq!(tm.hard_limit(), 11000);
+        let limits = Limits::from_go("go wtime 60000 btime 60000 movestogo 1").unwrap();
+        let tm = TimeManager::new(&limits, Col::White, 0, 20);
+        assert_eq!(tm.control(), TimeControl::Repeating);
+        assert_eq!(tm.hard_limit(), 54000);
+        assert_eq!(tm.soft_limit(), 54000);
+        let limits = Limits::from_go("go movetime 1000").unwrap();
+        let tm = TimeManager::new(&limits, Col::White, DEFAULT_MOVE_OVERHEAD, 20);
+        assert_eq!(tm.control(), TimeControl::MoveTime);
+        assert_eq!(tm.soft_limit(), 970);
+        assert_eq!(tm.hard_limit(), 970);
+        let limits = Limits::from_go("go infinite").unwrap();
+        let tm = TimeManager::new(&limits, Col::White, DEFAULT_MOVE_OVERHEAD, 1);
+        assert_eq!(tm.control(), TimeControl::Infinite);
+        assert!(!tm.should_stop(u64::MAX - 1));
+    }
+    #[test]
+    fn overhead() {
+        let limits = Limits::from_go("go wtime 40030 btime 40030").unwrap();
+        let tm = TimeManager::new(&limits, Col::White, DEFAULT_MOVE_OVERHEAD, 20);
+        assert_eq!(tm.soft_limit(), 1000);
+        let limits = Limits::from_go("go wtime 10 btime 10").unwrap();
+        let tm = TimeManager::new(&limits, Col::White, DEFAULT_MOVE_OVERHEAD, 20);
+        assert_eq!(tm.hard_limit(), 1);
+    }
+    // clocks up to i64::MAX are accepted, so the allocation must not overflow
+    #[test]
+    fn huge_clocks() {
+        for go in [
+            "go wtime 9000000000000000000 movestogo 1",
+            "go wtime 9223372036854775807 winc 9223372036854775807",
+            "go wtime 9223372036854775807 winc 9223372036854775807 movestogo 1",
+            "go wtime 1000 winc 9223372036854775807",
+        ] {
+            let limits = Limits::from_go(go).unwrap();
+            let tm = TimeManager::new(&limits, Col::White, DEFAULT_MOVE_OVERHEAD, 20);
+            assert!(tm.soft_limit() <= tm.hard_limit(), "{go}");
+            assert!(tm.hard_limit() < limits.wtime.unwrap(), "{go}");
+        }
+        let limits = Limits {
+            wtime: Some(u64::MAX),
+            winc: u64::MAX,
+            ..Limits::default()
+        };
+        let tm = TimeManager::new(&limits, Col::White, 0, 20);
+        assert_eq!(tm.hard_limit(), u64::MAX / 2);
+    }
+    #[test]
+    fn single_reply() {
+        let limits = Limits::from_go("go wtime 60000 btime 60000").unwrap();
+        let tm = TimeManager::new(&limits, Col::White, 0, 1);
+        assert_eq!(tm.soft_limit(), 0);
+        assert!(tm.should_stop(0));
+        assert_eq!(tm.hard_limit(), 6000);
+    }
+    #[test]
+    fn update() {
+        let limits = Limits::from_go("go wtime 60000 btime 60000").unwrap();
+        let mut tm = TimeManager::new(&limits, Col::White, 0, 20);
+        tm.update(false, 20);
+        assert_eq!(tm.soft_limit(), 1500);
+        tm.update(true, 25);
+        assert_eq!(tm.soft_limit(), 1950);
+        tm.update(true, -40);
+        assert_eq!(tm.soft_limit(), 3150);
+        tm.update(false, -40);
+        assert_eq!(tm.soft_limit(), 1950);
+        for _ in 0..10 {
+            tm.update(true, -40);
+        }
+        assert_eq!(tm.soft_limit(), 3300);
+        tm.update(true, -1000);
+        assert_eq!(tm.soft_limit(), 4050);
+    }
+    #[test]
+    fn should_stop() {
+        let limits = Limits::from_go("go wtime 60000 btime 60000").unwrap();
+        let tm = TimeManager::new(&limits, Col::White, 0, 20);
+        assert!(!tm.should_stop(1499));
+        assert!(tm.should_stop(1500));
+        assert!(!tm.out_of_time(5999));
+        assert!(tm.out_of_time(6000));
+    }
+}
@@ -0,0 +1,352 @@
+use std::fmt;
+
+use crate::castling::{back_rank_king, back_rank_square};
+use crate::consts::*;
+
+impl Board {
+    // Checks that the position could arise in a legal game of standard
+    // chess and returns every problem found. The board does not track the
+    // side to move, castling rights or the en passant square, so the
+    // caller passes them in, as parsed from the FEN.
+    pub fn validate(
+        &self,
+        turn: Col,
+        castling: &CastlingRights,
+        ep_square: Option<Square>,
+    ) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        for col in Col::all() {
+            self.validate_material(col, &mut violations);
+        }
+        for square in self.pawns() & (BB_RANK_1 | BB_RANK_8) {
+            violations.push(Violation::PawnOnBackRank(square));
+        }
+        self.validate_checks(turn, &mut violations);
+        self.validate_castling(castling, &mut violations);
+        if let Some(ep_square) = ep_square
+            && !self.valid_ep_square(turn, ep_square)
+        {
+            violations.push(Violation::InvalidEpSquare(ep_square));
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+    fn validate_material(&self, col: Col, violations: &mut Vec<Violation>) {
+        let count = |pt| self.by_piece(Piece::new(col, pt)).popcount();
+        let kings = count(PieceType::King);
+        if kings != 1 {
+            violations.push(Violation::KingCount(col, kings));
+        }
+        let pieces = self.by_col(col).popcount();
+        if pieces > 16 {
+            violations.push(Violation::TooManyPieces(col, pieces));
+        }
+        let pawns = count(PieceType::Pawn);
+        if pawns > 8 {
+            violations.push(Violation::TooManyPawns(col, pawns));
+        }
+        // every piece beyond the starting set must have been a pawn
+        let promoted = count(PieceType::Queen).saturating_sub(1)
+            + count(PieceType::Rook).saturating_sub(2)
+            + count(PieceType::Bishop).saturating_sub(2)
+            + count(PieceType::Knight).saturating_sub(2);
+        if promoted > 8 - pawns.min(8) {
+            violations.push(Violation::TooManyPromotedPieces(col, promoted));
+        }
+    }
+    fn validate_checks(&self, turn: Col, violations: &mut Vec<Violation>) {
+        let kings = self.kings();
+        if (kings & self.white()).popcount() != 1 || (kings & self.black()).popcount() != 1 {
+            return;
+        }
+        let Some(their_king) = self.king_of(!turn) else {
+            return;
+        };
+        if !self.atacks_to(their_king, turn, self.occupied).is_empty() {
+            violations.push(Violation::OpponentInCheck);
+        }
+        let Some(king) = self.king_of(turn) else {
+            return;
+        };
+        let checkers = self.atacks_to(king, !turn, self.occupied);
+        if !possible_checkers(king, checkers, self.sliders()) {
+            violations.push(Violation::ImpossibleCheck(checkers));
+        }
+    }
+    fn validate_castling(&self, castling: &CastlingRights, violations: &mut Vec<Violation>) {
+        for col in Col::all() {
+            if castling.rook(col, CastlingSide::KingSide).is_none()
+                && castling.rook(col, CastlingSide::QueenSide).is_none()
+            {
+                continue;
+            }
+            let Some(king) = back_rank_king(self, col) else {
+                violations.push(Violation::CastlingWithoutKing(col));
+                continue;
+            };
+            let rook = Piece::new(col, PieceType::Rook);
+            for side in CastlingSide::all() {
+                let Some(file) = castling.rook(col, side) else {
+                    continue;
+                };
+                let on_side = match side {
+                    CastlingSide::KingSide => file > king.file(),
+                    CastlingSide::QueenSide => file < king.file(),
+                };
+                if !on_side || self.piece_at(back_rank_square(col, file)) != Some(rook) {
+                    violations.push(Violation::CastlingWithoutRook(col, side));
+                }
+            }
+        }
+    }
+    // The square a pawn of the other side has just skipped over: it and the
+    // pawn's starting square are empty, and the pawn is in front of it.
+    fn valid_ep_square(&self, turn: Col, ep_square: Square) -> bool {
+        if ep_square.relative_to(turn).rank() != Rank::Six {
+            return false;
+        }
+        let (Some(pawn), Some(origin)) = (ep_square.pawn_push(!turn), ep_square.pawn_push(turn))
+        else {
+            return false;
+        };
+        self.piece_at(ep_square).is_none()
+            && self.piece_at(origin).is_none()
+            && self.piece_at(pawn) == Some(Piece::new(!turn, PieceType::Pawn))
+    }
+}
+
+// A single move can give at most two checks. A double check needs one
+// slider revealed by the move of the other checker, so two non-sliders
+// cannot check together, and neither can two pieces on one line through
+// the king.
+fn possible_checkers(king: Square, checkers: BitBoard, sliders: BitBoard) -> bool {
+    if !checkers.many() {
+        return true;
+    }
+    if checkers.popcount() > 2 || (checkers & sliders).is_empty() {
+        return false;
+    }
+    let (Some(a), Some(b)) = (checkers.lsb(), checkers.msb()) else {
+        return true;
+    };
+    let aligned = |attacks: fn(Square, BitBoard) -> BitBoard| {
+        attacks(king, BB_EMPTY).contains(checkers) && attacks(a, BB_EMPTY).contains_square(b)
+    };
+    !aligned(crate::attacks::rook_attacks) && !aligned(crate::attacks::bishop_attacks)
+}
+
+const fn col_name(col: Col) -> &'static str {
+    match col {
+        Col::White => "white",
+        Col::Black => "black",
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::KingCount(col, n) => write!(f, "{} has {n} kings", col_name(col)),
+            Self::PawnOnBackRank(square) => write!(f, "pawn on back rank at {square}"),
+            Self::TooManyPieces(col, n) => write!(f, "{} has {n} pieces", col_name(col)),
+            Self::TooManyPawns(col, n) => write!(f, "{} has {n} pawns", col_name(col)),
+            Self::TooManyPromotedPieces(col, n) => write!(
+                f,
+                "{} has {n} promoted pieces, more than its missing pawns",
+                col_name(col)
+            ),
+            Self::OpponentInCheck => write!(f, "the side not to move is in check"),
+            Self::ImpossibleCheck(checkers) => {
+                write!(f, "impossible check by {} pieces", checkers.popcount())
+            }
+            Self::CastlingWithoutKing(col) => {
+                write!(
+                    f,
+                    "{} castling rights without a king on the back rank",
+                    col_name(col)
+                )
+            }
+            Self::CastlingWithoutRook(col, side) => {
+                let side = match side {
+                    CastlingSide::KingSide => "king",
+                    CastlingSide::QueenSide => "queen",
+                };
+                write!(
+                    f,
+                    "{} {side}side castling right without a rook",
+                    col_name(col)
+                )
+            }
+            Self::InvalidEpSquare(square) => write!(f, "invalid en passant square {square}"),
+        }
+    }
+}
+impl std::error::Error for Violation {}
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::*;
+
+    fn board(pieces: &[(Square, Piece)]) -> Board {
+        let mut board = Board::empty();
+        for &(square, piece) in pieces {
+            board.set_piece_at(square, piece);
+        }
+        board
+    }
+    #[test]
+    fn start_positions() {
+        let rights = CastlingRights::CLASSICAL;
+        assert_eq!(Board::new().validate(Col::White, &rights, None), Ok(()));
+        for index in [0, 518, 959] {
+            let (board, rights) = StartPos::Chess960(index).setup().unwrap();
+            assert_eq!(board.validate(Col::White, &rights, None), Ok(()));
+        }
+    }
+    #[test]
+    fn reports_every_violation() {
+        let board = board(&[
+            (Square::A1, Piece::WK),
+            (Square::B1, Piece::WK),
+            (Square::C8, Piece::WP),
+            (Square::H1, Piece::BP),
+        ]);
+        let violations = board
+            .validate(Col::White, &CastlingRights::NONE, None)
+            .unwrap_err();
+        assert_eq!(
+            violations,
+            vec![
+                Violation::KingCount(Col::White, 2),
+                Violation::KingCount(Col::Black, 0),
+                Violation::PawnOnBackRank(Square::H1),
+                Violation::PawnOnBackRank(Square::C8),
+            ]
+        );
+        assert_eq!(violations[0].to_string(), "white has 2 kings");
+    }
+    #[test]
+    fn material() {
+        let mut board = Board::new();
+        board.set_piece_at(Square::E4, Piece::WP);
+        let rights = CastlingRights::CLASSICAL;
+        assert_eq!(
+            board.validate(Col::White, &rights, None),
+            Err(vec![
+                Violation::TooManyPieces(Col::White, 17),
+                Violation::TooManyPawns(Col::White, 9),
+            ])
+        );
+        // eight pawns and a second queen
+        let mut board = Board::new();
+        board.set_piece_at(Square::E3, Piece::WQ);
+        board.discard_piece_at(Square::B1);
+        assert_eq!(
+            board.validate(Col::White, &rights, None),
+            Err(vec![Violation::TooManyPromotedPieces(Col::White, 1)])
+        );
+        board.discard_piece_at(Square::E2);
+        assert_eq!(board.validate(Col::White, &rights, None), Ok(()));
+    }
+    #[test]
+    fn checks() {
+        let board = board(&[
+            (Square::E1, Piece::WK),
+            (Square::E8, Piece::BK),
+            (Square::E4, Piece::WR),
+        ]);
+        let rights = CastlingRights::NONE;
+        assert_eq!(board.validate(Col::Black, &rights, None), Ok(()));
+        assert_eq!(
+            board.validate(Col::White, &rights, None),
+            Err(vec![Violation::OpponentInCheck])
+        );
+        // discovered check from a rook after a knight move
+        let board = board_with(&[(Square::E4, Piece::BR), (Square::D3, Piece::BN)]);
+        assert_eq!(board.validate(Col::White, &rights, None), Ok(()));
+        // two knights cannot check at once
+        let board = board_with(&[(Square::D3, Piece::BN), (Square::F3, Piece::BN)]);
+        assert_eq!(
+            board.validate(Col::White, &rights, None),
+            Err(vec![Violation::ImpossibleCheck(
+                Square::D3.as_bb() | Square::F3.as_bb()
+            )])
+        );
+        // nor two pieces on one line through the king
+        let board = board_with(&[(Square::E5, Piece::BR), (Square::B4, Piece::BB)]);
+        assert!(possible(&board));
+        let board = board_with(&[(Square::B1, Piece::BR), (Square::H1, Piece::BR)]);
+        assert!(!possible(&board));
+        // and three checkers are always too many
+        let board = board_with(&[
+            (Square::E4, Piece::BR),
+            (Square::D3, Piece::BN),
+            (Square::F2, Piece::BP),
+        ]);
+        assert!(!possible(&board));
+    }
+    fn board_with(checkers: &[(Square, Piece)]) -> Board {
+        let mut pieces = vec![(Square::E1, Piece::WK), (Square::H8, Piece::BK)];
+        pieces.extend_from_slice(checkers);
+        board(&pieces)
+    }
+    fn possible(board: &Board) -> bool {
+        !board
+            .validate(Col::White, &CastlingRights::NONE, None)
+            .err()
+            .unwrap_or_default()
+            .iter()
+            .any(|v| matches!(v, Violation::ImpossibleCheck(_)))
+    }
+    #[test]
+    fn castling() {
+        let mut board = Board::new();
+        board.discard_piece_at(Square::H1);
+        board.discard_piece_at(Square::E8);
+        board.set_piece_at(Square::E6, Piece::BK);
+        assert_eq!(
+            board.validate(Col::White, &CastlingRights::CLASSICAL, None),
+            Err(vec![
+                Violation::CastlingWithoutRook(Col::White, CastlingSide::KingSide),
+                Violation::CastlingWithoutKing(Col::Black),
+            ])
+        );
+        let mut rights = CastlingRights::NONE;
+        rights.set(Col::White, CastlingSide::QueenSide, File::A);
+        assert_eq!(board.validate(Col::White, &rights, None), Ok(()));
+        // a queenside right must name a rook on the queenside
+        rights.set(Col::White, CastlingSide::QueenSide, File::H);
+        board.set_piece_at(Square::H1, Piece::WR);
+        assert_eq!(
+            board.validate(Col::White, &rights, None),
+            Err(vec![Violation::CastlingWithoutRook(
+                Col::White,
+                CastlingSide::QueenSide
+            )])
+        );
+    }
+    #[test]
+    fn ep_square() {
+        // after 1. e4
+        let mut board = Board::new();
+        board.discard_piece_at(Square::E2);
+        board.set_piece_at(Square::E4, Piece::WP);
+        let rights = CastlingRights::CLASSICAL;
+        assert_eq!(
+            board.validate(Col::Black, &rights, Some(Square::E3)),
+            Ok(())
+        );
+        for (turn, ep_square) in [
+            (Col::White, Square::E3),
+            (Col::Black, Square::D3),
+            (Col::Black, Square::E6),
+        ] {
+            assert_eq!(
+                board.validate(turn, &rights, Some(ep_square)),
+                Err(vec![Violation::InvalidEpSquare(ep_square)])
+            );
+        }
+    }
+}